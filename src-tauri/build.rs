@@ -1,3 +1,30 @@
+// Every command gets `allow-<command>` permissions, so a window can only call
+// the commands its capability grants.
+const COMMANDS: &[&str] = &[
+    "save_shortcut",
+    "delete_shortcut",
+    "get_shortcuts",
+    "get_shortcut_by_id",
+    "get_shortcut_dependants",
+    "update_shortcut",
+    "check_action_paths",
+    "list_scripts",
+    "get_script",
+    "create_script",
+    "update_script",
+    "delete_script",
+    "get_raw_settings",
+    "list_installed_applications",
+    "list_running_actions",
+    "cancel_running_action",
+    "get_popup_request",
+    "answer_popup",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build")
 }
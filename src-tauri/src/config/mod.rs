@@ -7,6 +7,9 @@ static CONFIG: OnceCell<Mutex<AppConfig>> = OnceCell::new();
 pub struct AppConfig {
     pub data_dir: PathBuf,
     pub settings_file: PathBuf,
+    pub state_file: PathBuf,
+    pub scripts_dir: PathBuf,
+    pub logs_dir: PathBuf,
 }
 
 impl AppConfig {
@@ -18,12 +21,25 @@ impl AppConfig {
         let home_dir = dirs::home_dir().ok_or("Failed to get home directory")?;
         let data_dir = home_dir.join(".shortcut-artisan");
         let settings_file = data_dir.join("settings.json");
+        let state_file = data_dir.join("state.json");
+        let scripts_dir = data_dir.join("scripts");
+        let logs_dir = data_dir.join("logs");
 
         if !data_dir.exists() {
             std::fs::create_dir_all(&data_dir)
                 .map_err(|e| format!("Failed to create config directory: {}", e))?;
         }
 
+        if !scripts_dir.exists() {
+            std::fs::create_dir_all(&scripts_dir)
+                .map_err(|e| format!("Failed to create scripts directory: {}", e))?;
+        }
+
+        if !logs_dir.exists() {
+            std::fs::create_dir_all(&logs_dir)
+                .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+        }
+
         if !settings_file.exists() {
             std::fs::File::create(&settings_file)
                 .map_err(|e| format!("Failed to create settings file: {}", e))?;
@@ -35,6 +51,9 @@ impl AppConfig {
         let config = AppConfig {
             data_dir,
             settings_file,
+            state_file,
+            scripts_dir,
+            logs_dir,
         };
 
         CONFIG
@@ -1,25 +1,118 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ShortcutAction {
     pub action_type: ActionType,
     pub parameters: ActionParameters,
+    pub wait: Option<bool>, // by default only scripts are waited for
+    pub on_failure: Option<OnFailure>, // Stop by default
+    pub actions: Option<Vec<ShortcutAction>>, // a Parallel group's members, a Toggle's odd presses
+    pub condition: Option<ActionCondition>,
+    pub else_actions: Option<Vec<ShortcutAction>>, // run when the condition doesn't hold
+    pub alternate_actions: Option<Vec<ShortcutAction>>, // a Toggle's even presses
+    pub choices: Option<Vec<MenuChoice>>,          // for ChoiceMenu
+}
+
+impl ShortcutAction {
+    /// The action lists nested in the action: group members, branches and
+    /// menu choices.
+    pub fn nested_actions(&self) -> impl Iterator<Item = &[ShortcutAction]> {
+        [&self.actions, &self.else_actions, &self.alternate_actions]
+            .into_iter()
+            .flatten()
+            .map(Vec::as_slice)
+            .chain(
+                self.choices
+                    .iter()
+                    .flatten()
+                    .map(|choice| choice.actions.as_slice()),
+            )
+    }
+}
+
+/// Checks that every `ChoiceMenu` among the actions has choices with names,
+/// and that their accelerators are single, distinct keys.
+pub fn validate_menus(actions: &[ShortcutAction]) -> Result<(), String> {
+    for action in actions {
+        if let ActionType::ChoiceMenu = action.action_type {
+            let choices = action.choices.as_deref().unwrap_or_default();
+            if choices.is_empty() {
+                return Err("No choices specified for ChoiceMenu action".to_string());
+            }
+
+            let mut keys: Vec<char> = Vec::new();
+            for choice in choices {
+                if choice.name.trim().is_empty() {
+                    return Err("Every choice of a ChoiceMenu action needs a name".to_string());
+                }
+                let Some(key) = &choice.key else {
+                    continue;
+                };
+                let mut chars = key.chars();
+                let (Some(key), None) = (chars.next(), chars.next()) else {
+                    return Err(format!(
+                        "Key {:?} of choice {} must be a single key",
+                        key, choice.name
+                    ));
+                };
+                let key = key.to_ascii_lowercase();
+                if keys.contains(&key) {
+                    return Err(format!("Key {} is used by more than one choice", key));
+                }
+                keys.push(key);
+            }
+        }
+
+        for nested in action.nested_actions() {
+            validate_menus(nested)?;
+        }
+    }
+    Ok(())
+}
+
+/// An entry of a `ChoiceMenu`, running its actions when picked.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MenuChoice {
+    pub name: String,
+    pub key: Option<String>, // single key accelerator
+    pub actions: Vec<ShortcutAction>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ActionType {
     OpenFolder,
     OpenFile,
+    OpenUrl,
 
     OpenApplication,
     QuitApplication,
     HideApplication,
     FocusApplication,
+    ToggleApplication,
 
     MinimizeWindow,
     MaximizeWindow,
+    MoveResizeWindow,
+
+    SendKeys,
+    TypeText,
+    CopyToClipboard,
 
     RunShellScript,
+
+    CopyPath,
+    MovePath,
+    RenamePath,
+
+    Delay,
+    Parallel,
+    RunShortcut,
+
+    SetState,
+    Toggle,
+    ChoiceMenu,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,18 +120,145 @@ pub struct ActionParameters {
     pub path: Option<String>,
     pub source_path: Option<String>,
     pub target_path: Option<String>,
+    pub overwrite: Option<OverwritePolicy>,
+    pub base_dir: Option<String>, // relative paths resolve against it, home by default
+
+    pub url: Option<String>,
+    pub browser: Option<String>,
+    pub browser_profile: Option<String>,
 
     pub app_name: Option<String>,
+    pub args: Option<Vec<String>>, // passed as is, without a shell
+    pub cwd: Option<String>,
+    pub env: Option<HashMap<String, String>>,
+    pub stdin_from_previous: Option<bool>, // the previous action's stdout
 
     pub key: Option<String>,
     pub modifiers: Option<Vec<String>>, // ["cmd", "shift", etc.]
+    pub key_delay_ms: Option<u64>,
+    pub text: Option<String>,
 
     pub window_width: Option<i32>,
     pub window_height: Option<i32>,
     pub window_x: Option<i32>,
     pub window_y: Option<i32>,
+    pub geometry_unit: Option<GeometryUnit>,
+    pub snap_position: Option<SnapPosition>,
 
     pub script: Option<String>,
+    pub script_file: Option<String>, // name of a script in data_dir/scripts
+    pub interpreter: Option<ScriptInterpreter>,
+    pub shebang: Option<String>, // for the Custom interpreter, e.g. "#!/usr/bin/env ruby"
+
+    pub delay_ms: Option<u64>,
+    pub join: Option<JoinPolicy>,    // for Parallel, All by default
+    pub shortcut_id: Option<String>, // for RunShortcut
+
+    pub state_key: Option<String>,
+    pub state_value: Option<String>, // SetState without a value removes the key
+    pub state_scope: Option<StateScope>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ScriptInterpreter {
+    Sh,
+    Bash,
+    Zsh,
+    Fish,
+    Python3,
+    Node,
+    Custom,
+}
+
+/// Safety limits for the `RunShellScript` actions of a shortcut. Unset limits
+/// don't apply.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ScriptLimits {
+    pub timeout_secs: Option<u64>,     // wall-clock time
+    pub max_output_bytes: Option<u64>, // stdout and stderr together
+    pub cpu_secs: Option<u64>,
+    pub memory_mb: Option<u64>, // address space
+    pub nice: Option<i32>,
+    pub isolate_network: Option<bool>, // run in an empty network namespace
+    pub env_allowlist: Option<Vec<String>>, // only these variables (and PATH) are inherited
+}
+
+/// What the pipeline does when an action fails.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum OnFailure {
+    Stop,
+    Continue,
+    Retry {
+        times: u32,
+        backoff_ms: Option<u64>, // doubled after every attempt
+    },
+}
+
+/// Condition under which an action runs.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ActionCondition {
+    ExitCode { equals: i32 },          // of the previous action
+    StdoutMatches { pattern: String }, // regex, on the previous action's stdout
+    FileExists { path: String },
+    ProcessRunning { name: String },
+    TimeOfDay { from: String, to: String }, // "HH:MM", may wrap past midnight
+    Weekday { days: Vec<Weekday> },
+    FocusedApp { app_name: String }, // when the shortcut was pressed
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// When a Parallel group is done: once all of its actions are, or as soon as
+/// the first one succeeds.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum JoinPolicy {
+    All,
+    First,
+}
+
+/// Whose state an action reads and writes: the shortcut's own, or the one
+/// shared by all shortcuts.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum StateScope {
+    #[default]
+    Shortcut,
+    Global,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum OverwritePolicy {
+    Skip,
+    Replace,
+    AutoSuffix,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum GeometryUnit {
+    Pixels,
+    Percent, // of the current monitor's work area
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum SnapPosition {
+    LeftHalf,
+    RightHalf,
+    TopHalf,
+    BottomHalf,
+    LeftThird,
+    CenterThird,
+    RightThird,
+    LeftTwoThirds,
+    RightTwoThirds,
+    Center,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
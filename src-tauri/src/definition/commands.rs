@@ -1,4 +1,6 @@
+use crate::definition::action::ShortcutAction;
 use crate::definition::definition_facade::DefinitionFacade;
+use crate::definition::script_repository::ScriptSummary;
 use crate::definition::shortcut::{Shortcut, ShortcutRequestPayload};
 use tauri::AppHandle;
 
@@ -39,6 +41,15 @@ pub async fn get_shortcut_by_id(
     facade.get_shortcut_by_id(&id)
 }
 
+#[tauri::command]
+pub async fn get_shortcut_dependants(
+    app_handle: AppHandle,
+    id: String,
+) -> Result<Vec<Shortcut>, String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    facade.get_shortcut_dependants(&id)
+}
+
 #[tauri::command]
 pub async fn update_shortcut(
     app_handle: AppHandle,
@@ -48,3 +59,50 @@ pub async fn update_shortcut(
     let facade = DefinitionFacade::new(app_handle)?;
     facade.update_shortcut(&id, payload).await
 }
+
+#[tauri::command]
+pub async fn check_action_paths(
+    app_handle: AppHandle,
+    actions: Vec<ShortcutAction>,
+) -> Result<Vec<String>, String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    Ok(facade.check_action_paths(&actions))
+}
+
+#[tauri::command]
+pub async fn list_scripts(app_handle: AppHandle) -> Result<Vec<ScriptSummary>, String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    facade.list_scripts()
+}
+
+#[tauri::command]
+pub async fn get_script(app_handle: AppHandle, name: String) -> Result<String, String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    facade.get_script(&name)
+}
+
+#[tauri::command]
+pub async fn create_script(
+    app_handle: AppHandle,
+    name: String,
+    content: String,
+) -> Result<(), String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    facade.create_script(&name, &content)
+}
+
+#[tauri::command]
+pub async fn update_script(
+    app_handle: AppHandle,
+    name: String,
+    content: String,
+) -> Result<(), String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    facade.update_script(&name, &content)
+}
+
+#[tauri::command]
+pub async fn delete_script(app_handle: AppHandle, name: String) -> Result<(), String> {
+    let facade = DefinitionFacade::new(app_handle)?;
+    facade.delete_script(&name)
+}
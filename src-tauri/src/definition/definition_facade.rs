@@ -1,22 +1,28 @@
-use crate::definition::shortcut::{Shortcut, ShortcutRequestPayload};
-use crate::execution::ExecutionFacade;
+use crate::definition::action::{validate_menus, ShortcutAction};
+use crate::definition::shortcut::{validate_prompts, Shortcut, ShortcutRequestPayload};
+use crate::execution::{missing_action_paths, ExecutionFacade, StateStore};
 use tauri::AppHandle;
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
+use super::script_repository::{ScriptRepository, ScriptSummary};
+use super::shortcut_graph::{dependants, validate_references};
 use super::shortcut_repository::ShortcutRepository;
 use uuid::Uuid;
 
 pub struct DefinitionFacade {
     app_handle: AppHandle,
     shortcut_repository: ShortcutRepository,
+    script_repository: ScriptRepository,
 }
 
 impl DefinitionFacade {
     pub fn new(app_handle: AppHandle) -> Result<Self, String> {
         let shortcut_repository = ShortcutRepository::new()?;
+        let script_repository = ScriptRepository::new()?;
         Ok(Self {
             app_handle,
             shortcut_repository,
+            script_repository,
         })
     }
 
@@ -31,8 +37,18 @@ impl DefinitionFacade {
             enabled: true, // TODO: Implement enabled
             actions: shortcut.actions.clone(),
             scope: None, // TODO: Implement scope
+            limits: shortcut.limits.clone(),
+            execution_mode: shortcut.execution_mode,
+            cycle_reset_after_secs: shortcut.cycle_reset_after_secs,
+            prompts: shortcut.prompts.clone(),
+            require_confirmation: shortcut.require_confirmation,
+            confirmation_timeout_secs: shortcut.confirmation_timeout_secs,
         };
 
+        validate_prompts(shortcut.prompts.as_deref().unwrap_or_default())?;
+        validate_menus(&shortcut.actions)?;
+        validate_references(&shortcut, &self.shortcut_repository.get_all()?)?;
+
         self.shortcut_repository
             .save(&shortcut)
             .expect("Failed to save shortcut");
@@ -55,11 +71,42 @@ impl DefinitionFacade {
         Ok(shortcut)
     }
 
+    /// Paths the actions read from that don't exist yet. These are only warnings:
+    /// the path may well exist by the time the shortcut runs.
+    pub fn check_action_paths(&self, actions: &[ShortcutAction]) -> Vec<String> {
+        let warnings = action_path_warnings(actions);
+
+        for warning in &warnings {
+            log::warn!("{}", warning);
+        }
+        warnings
+    }
+
     pub fn get_current_shortcut(&self) -> Result<Shortcut, String> {
         self.shortcut_repository.get_current()
     }
 
+    /// Shortcuts that run the shortcut `id` and stop working once it's deleted.
+    pub fn get_shortcut_dependants(&self, id: &str) -> Result<Vec<Shortcut>, String> {
+        let shortcuts = self.shortcut_repository.get_all()?;
+        Ok(dependants(id, &shortcuts).into_iter().cloned().collect())
+    }
+
+    /// Shortcuts that run the deleted one are kept and fail when they reach
+    /// it; the frontend asks before deleting such a shortcut.
     pub async fn delete_shortcut(&self, id: &str) -> Result<(), String> {
+        for dependant in self.get_shortcut_dependants(id)? {
+            log::warn!(
+                "Deleting shortcut {} breaks {}, which runs it",
+                id,
+                dependant.command_name
+            );
+        }
+
+        if let Err(e) = StateStore::global().remove_shortcut(id) {
+            log::warn!("Failed to clear state of shortcut {}: {}", id, e);
+        }
+
         if let Ok(shortcut) = self.shortcut_repository.get_current() {
             if shortcut.id == id {
                 let execution_facade = ExecutionFacade::new(self.app_handle.clone());
@@ -109,12 +156,57 @@ impl DefinitionFacade {
             enabled: true,
             actions: payload.actions,
             scope: None,
+            limits: payload.limits,
+            execution_mode: payload.execution_mode,
+            cycle_reset_after_secs: payload.cycle_reset_after_secs,
+            prompts: payload.prompts,
+            require_confirmation: payload.require_confirmation,
+            confirmation_timeout_secs: payload.confirmation_timeout_secs,
         };
+
+        validate_prompts(updated_shortcut.prompts.as_deref().unwrap_or_default())?;
+        validate_menus(&updated_shortcut.actions)?;
+        validate_references(&updated_shortcut, &self.shortcut_repository.get_all()?)?;
         
         self.shortcut_repository.save(&updated_shortcut)?;
         
         Ok(updated_shortcut)
     }
-}
 
+    pub fn list_scripts(&self) -> Result<Vec<ScriptSummary>, String> {
+        self.script_repository.list()
+    }
+
+    pub fn get_script(&self, name: &str) -> Result<String, String> {
+        self.script_repository.read(name)
+    }
+
+    pub fn create_script(&self, name: &str, content: &str) -> Result<(), String> {
+        log::info!("Creating script: {}", name);
+        self.script_repository.write(name, content, true)
+    }
+
+    pub fn update_script(&self, name: &str, content: &str) -> Result<(), String> {
+        log::info!("Updating script: {}", name);
+        self.script_repository.write(name, content, false)
+    }
 
+    pub fn delete_script(&self, name: &str) -> Result<(), String> {
+        log::info!("Deleting script: {}", name);
+        self.script_repository.delete(name)
+    }
+}
+
+/// Path warnings for the actions, including the ones nested in other actions.
+fn action_path_warnings(actions: &[ShortcutAction]) -> Vec<String> {
+    actions
+        .iter()
+        .flat_map(|action| {
+            let mut warnings = missing_action_paths(&action.action_type, &action.parameters);
+            for nested in action.nested_actions() {
+                warnings.extend(action_path_warnings(nested));
+            }
+            warnings
+        })
+        .collect()
+}
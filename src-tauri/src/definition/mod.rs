@@ -1,6 +1,8 @@
 pub(crate) mod action;
 pub mod commands;
 pub mod definition_facade;
+pub mod script_repository;
 pub mod shortcut;
+pub mod shortcut_graph;
 pub mod shortcut_repository;
 
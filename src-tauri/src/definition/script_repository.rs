@@ -0,0 +1,112 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::config::AppConfig;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptSummary {
+    pub name: String,
+    pub shebang: Option<String>,
+}
+
+/// Script files managed by the app, stored flat in `data_dir/scripts/`.
+pub struct ScriptRepository {
+    scripts_dir: PathBuf,
+}
+
+impl ScriptRepository {
+    pub fn new() -> Result<Self, String> {
+        let config = AppConfig::global().lock().map_err(|e| {
+            log::error!("Failed to lock config: {}", e);
+            format!("Failed to lock config: {}", e)
+        })?;
+
+        Ok(Self {
+            scripts_dir: config.scripts_dir.clone(),
+        })
+    }
+
+    /// Path of the script called `name`. Names are plain file names, so a
+    /// script reference can't point outside the scripts directory.
+    pub fn path(&self, name: &str) -> Result<PathBuf, String> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' '));
+
+        if !valid {
+            log::error!("Invalid script name: {:?}", name);
+            return Err(format!("Invalid script name: {:?}", name));
+        }
+
+        Ok(self.scripts_dir.join(name))
+    }
+
+    pub fn list(&self) -> Result<Vec<ScriptSummary>, String> {
+        let entries = fs::read_dir(&self.scripts_dir).map_err(|e| {
+            log::error!("Failed to read scripts directory: {}", e);
+            e.to_string()
+        })?;
+
+        let mut scripts: Vec<ScriptSummary> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| !name.starts_with('.'))
+            .map(|name| ScriptSummary {
+                shebang: self.read(&name).ok().and_then(|content| {
+                    content
+                        .lines()
+                        .next()
+                        .filter(|line| line.starts_with("#!"))
+                        .map(str::to_string)
+                }),
+                name,
+            })
+            .collect();
+        scripts.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(scripts)
+    }
+
+    pub fn read(&self, name: &str) -> Result<String, String> {
+        let path = self.path(name)?;
+        fs::read_to_string(&path).map_err(|e| {
+            log::error!("Failed to read script {}: {}", name, e);
+            format!("Failed to read script {}: {}", name, e)
+        })
+    }
+
+    /// Writes the script, creating it only when `create` is set and updating it
+    /// only when it isn't, so neither can clobber the other by accident.
+    pub fn write(&self, name: &str, content: &str, create: bool) -> Result<(), String> {
+        let path = self.path(name)?;
+        if create && path.exists() {
+            return Err(format!("Script {} already exists", name));
+        }
+        if !create && !path.exists() {
+            return Err(format!("Script {} not found", name));
+        }
+
+        fs::write(&path, content).map_err(|e| {
+            log::error!("Failed to write script {}: {}", name, e);
+            format!("Failed to write script {}: {}", name, e)
+        })?;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())?;
+
+        log::debug!("Script {} saved", name);
+        Ok(())
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        let path = self.path(name)?;
+        fs::remove_file(&path).map_err(|e| {
+            log::error!("Failed to delete script {}: {}", name, e);
+            format!("Failed to delete script {}: {}", name, e)
+        })
+    }
+}
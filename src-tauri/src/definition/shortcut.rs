@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::action::{ActionScope, ShortcutAction};
+use super::action::{ActionScope, ScriptLimits, ShortcutAction};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Shortcut {
@@ -11,6 +11,53 @@ pub struct Shortcut {
     pub enabled: bool,
     pub actions: Vec<ShortcutAction>,
     pub scope: Option<ActionScope>,
+    pub limits: Option<ScriptLimits>,
+    pub execution_mode: Option<ExecutionMode>,
+    pub cycle_reset_after_secs: Option<u64>, // idle time before a cycle starts over
+    pub prompts: Option<Vec<ParameterPrompt>>,
+    pub require_confirmation: Option<bool>,
+    pub confirmation_timeout_secs: Option<u64>, // unanswered confirmations cancel
+}
+
+/// How a press runs the shortcut's actions.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum ExecutionMode {
+    #[default]
+    Sequence, // all actions, one after another
+    Cycle, // the next action on each press, wrapping around
+}
+
+/// A value asked for in a popup when the shortcut fires, available to its
+/// actions as `{prompt.<name>}`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParameterPrompt {
+    pub name: String,
+    pub label: Option<String>, // the name by default
+    pub default: Option<String>,
+}
+
+/// Prompt names must be unique and usable in templates.
+pub fn validate_prompts(prompts: &[ParameterPrompt]) -> Result<(), String> {
+    for (index, prompt) in prompts.iter().enumerate() {
+        let valid = !prompt.name.is_empty()
+            && prompt
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "Invalid prompt name {:?}, use letters, digits and _",
+                prompt.name
+            ));
+        }
+        if prompts[..index]
+            .iter()
+            .any(|other| other.name == prompt.name)
+        {
+            return Err(format!("Prompt {} is defined twice", prompt.name));
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,4 +66,10 @@ pub struct ShortcutRequestPayload {
     pub name: String,
     pub description: Option<String>,
     pub actions: Vec<ShortcutAction>,
+    pub limits: Option<ScriptLimits>,
+    pub execution_mode: Option<ExecutionMode>,
+    pub cycle_reset_after_secs: Option<u64>,
+    pub prompts: Option<Vec<ParameterPrompt>>,
+    pub require_confirmation: Option<bool>,
+    pub confirmation_timeout_secs: Option<u64>,
 }
@@ -0,0 +1,187 @@
+use super::action::{ActionType, ShortcutAction};
+use super::shortcut::Shortcut;
+
+/// Ids of the shortcuts the actions run through `RunShortcut`, including those
+/// nested in other actions.
+pub fn referenced_shortcuts(actions: &[ShortcutAction]) -> Vec<&str> {
+    actions
+        .iter()
+        .flat_map(|action| {
+            let mut references = Vec::new();
+            if let (ActionType::RunShortcut, Some(id)) =
+                (&action.action_type, &action.parameters.shortcut_id)
+            {
+                references.push(id.as_str());
+            }
+            for nested in action.nested_actions() {
+                references.extend(referenced_shortcuts(nested));
+            }
+            references
+        })
+        .collect()
+}
+
+/// Checks that the shortcuts `shortcut` runs exist and that none of them leads
+/// back into a cycle. `shortcuts` are the saved ones; a saved version of
+/// `shortcut` itself is ignored.
+pub fn validate_references(shortcut: &Shortcut, shortcuts: &[Shortcut]) -> Result<(), String> {
+    let find = |id: &str| -> Option<&Shortcut> {
+        if id == shortcut.id {
+            Some(shortcut)
+        } else {
+            shortcuts.iter().find(|saved| saved.id == id)
+        }
+    };
+
+    let mut path = vec![shortcut];
+    visit(shortcut, &find, &mut path)
+}
+
+fn visit<'a>(
+    current: &'a Shortcut,
+    find: &dyn Fn(&str) -> Option<&'a Shortcut>,
+    path: &mut Vec<&'a Shortcut>,
+) -> Result<(), String> {
+    for id in referenced_shortcuts(&current.actions) {
+        let Some(referenced) = find(id) else {
+            // Broken references further down are the other shortcuts' business.
+            if path.len() > 1 {
+                continue;
+            }
+            return Err(format!(
+                "{} runs shortcut {}, which does not exist",
+                current.command_name, id
+            ));
+        };
+
+        if path.iter().any(|visited| visited.id == referenced.id) {
+            let cycle: Vec<&str> = path
+                .iter()
+                .skip_while(|visited| visited.id != referenced.id)
+                .chain([&referenced])
+                .map(|visited| visited.command_name.as_str())
+                .collect();
+            return Err(format!(
+                "Shortcuts can't run each other in a cycle: {}",
+                cycle.join(" → ")
+            ));
+        }
+
+        path.push(referenced);
+        visit(referenced, find, path)?;
+        path.pop();
+    }
+
+    Ok(())
+}
+
+/// The shortcuts that run the shortcut `id`.
+pub fn dependants<'a>(id: &str, shortcuts: &'a [Shortcut]) -> Vec<&'a Shortcut> {
+    shortcuts
+        .iter()
+        .filter(|shortcut| shortcut.id != id)
+        .filter(|shortcut| referenced_shortcuts(&shortcut.actions).contains(&id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn run(id: &str) -> Value {
+        json!({ "action_type": "RunShortcut", "parameters": { "shortcut_id": id } })
+    }
+
+    fn shortcut(id: &str, actions: Vec<Value>) -> Shortcut {
+        serde_json::from_value(json!({
+            "id": id,
+            "key_combination": "Ctrl+Alt+K",
+            "command_name": id.to_uppercase(),
+            "enabled": true,
+            "actions": actions,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn collects_nested_references() {
+        let parallel = json!({
+            "action_type": "Parallel",
+            "parameters": {},
+            "actions": [run("b")],
+            "else_actions": [run("c")],
+        });
+        let a = shortcut("a", vec![run("d"), parallel]);
+
+        assert_eq!(referenced_shortcuts(&a.actions), ["d", "b", "c"]);
+    }
+
+    #[test]
+    fn accepts_existing_references() {
+        let b = shortcut("b", vec![]);
+        let a = shortcut("a", vec![run("b"), run("b")]);
+
+        assert!(validate_references(&a, &[b]).is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_references() {
+        let a = shortcut("a", vec![run("b")]);
+
+        assert_eq!(
+            validate_references(&a, &[]).unwrap_err(),
+            "A runs shortcut b, which does not exist"
+        );
+    }
+
+    #[test]
+    fn rejects_self_reference() {
+        let a = shortcut("a", vec![run("a")]);
+
+        assert_eq!(
+            validate_references(&a, &[]).unwrap_err(),
+            "Shortcuts can't run each other in a cycle: A → A"
+        );
+    }
+
+    #[test]
+    fn rejects_indirect_cycles() {
+        let b = shortcut("b", vec![run("c")]);
+        let c = shortcut("c", vec![run("a")]);
+        // The saved version of the edited shortcut doesn't count.
+        let saved_a = shortcut("a", vec![]);
+        let a = shortcut("a", vec![run("b")]);
+
+        assert_eq!(
+            validate_references(&a, &[saved_a, b, c]).unwrap_err(),
+            "Shortcuts can't run each other in a cycle: A → B → C → A"
+        );
+    }
+
+    #[test]
+    fn ignores_broken_references_further_down() {
+        let b = shortcut("b", vec![run("gone")]);
+        let a = shortcut("a", vec![run("b")]);
+
+        assert!(validate_references(&a, &[b]).is_ok());
+    }
+
+    #[test]
+    fn lists_dependants() {
+        let shortcuts = [
+            shortcut("a", vec![run("a"), run("c")]),
+            shortcut("b", vec![run("c")]),
+            shortcut("c", vec![]),
+            shortcut("d", vec![run("b")]),
+        ];
+
+        let ids: Vec<&str> = dependants("c", &shortcuts)
+            .iter()
+            .map(|shortcut| shortcut.id.as_str())
+            .collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(dependants("a", &shortcuts).is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+fn read_commands(primary: bool) -> Vec<(&'static str, Vec<&'static str>)> {
+    match (is_wayland(), primary) {
+        (true, false) => vec![("wl-paste", vec!["--no-newline"])],
+        (true, true) => vec![("wl-paste", vec!["--no-newline", "--primary"])],
+        (false, false) => vec![
+            ("xclip", vec!["-selection", "clipboard", "-o"]),
+            ("xsel", vec!["--clipboard", "--output"]),
+        ],
+        (false, true) => vec![
+            ("xclip", vec!["-selection", "primary", "-o"]),
+            ("xsel", vec!["--primary", "--output"]),
+        ],
+    }
+}
+
+fn write_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    if is_wayland() {
+        vec![("wl-copy", vec![])]
+    } else {
+        vec![
+            ("xclip", vec!["-selection", "clipboard", "-i"]),
+            ("xsel", vec!["--clipboard", "--input"]),
+        ]
+    }
+}
+
+/// Reads the clipboard with the usual command line tools: wl-clipboard on
+/// Wayland, xclip (or xsel) on X11. Fails when the clipboard is empty or holds
+/// no text, e.g. an image.
+pub fn read_clipboard() -> Result<String, String> {
+    read_selection(false)
+}
+
+/// Reads the primary selection (the currently selected text).
+pub fn read_primary_selection() -> Result<String, String> {
+    read_selection(true)
+}
+
+fn read_selection(primary: bool) -> Result<String, String> {
+    let mut last_error = String::from("No clipboard tool available");
+
+    for (program, args) in read_commands(primary) {
+        match Command::new(program).args(&args).output() {
+            Ok(output) if output.status.success() => {
+                return Ok(String::from_utf8_lossy(&output.stdout).to_string());
+            }
+            // The tools also exit with an error when there is no text to read.
+            Ok(output) => {
+                last_error = format!(
+                    "{} exited with {}: {}",
+                    program,
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )
+            }
+            Err(e) => last_error = format!("Failed to run {}: {}", program, e),
+        }
+    }
+
+    log::error!("Failed to read clipboard: {}", last_error);
+    Err(last_error)
+}
+
+pub fn write_clipboard(text: &str) -> Result<(), String> {
+    let mut last_error = String::from("No clipboard tool available");
+
+    for (program, args) in write_commands() {
+        let mut child = match Command::new(program)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                last_error = format!("Failed to run {}: {}", program, e);
+                continue;
+            }
+        };
+
+        // The child is waited for even when writing fails, so it doesn't linger.
+        let written = match child.stdin.take() {
+            Some(mut stdin) => stdin.write_all(text.as_bytes()),
+            None => Ok(()),
+        };
+
+        // Both tools fork a background process that keeps serving the selection.
+        let status = child.wait().map_err(|e| e.to_string())?;
+        if let Err(e) = written {
+            last_error = format!("Failed to write to {}: {}", program, e);
+            continue;
+        }
+        if status.success() {
+            return Ok(());
+        }
+        last_error = format!("{} exited with {}", program, status);
+    }
+
+    log::error!("Failed to write clipboard: {}", last_error);
+    Err(last_error)
+}
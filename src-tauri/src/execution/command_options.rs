@@ -0,0 +1,43 @@
+use std::process::Command;
+
+use super::path_resolver::resolve_path;
+use crate::definition::action::ActionParameters;
+
+/// Applies the `cwd` and `env` action parameters to a command about to be
+/// spawned. Without a `cwd` the command starts in the home directory rather
+/// than wherever the app itself was started from, unless the command already
+/// has one (the `Path` of a desktop entry).
+pub fn apply_command_options(
+    command: &mut Command,
+    parameters: &ActionParameters,
+) -> Result<(), String> {
+    match parameters.cwd.as_deref().filter(|cwd| !cwd.is_empty()) {
+        Some(cwd) => {
+            let cwd = resolve_path(cwd, parameters.base_dir.as_deref())?;
+            if !cwd.is_dir() {
+                log::error!("Working directory does not exist: {}", cwd.display());
+                return Err(format!(
+                    "Working directory does not exist: {}",
+                    cwd.display()
+                ));
+            }
+            command.current_dir(cwd);
+        }
+        None if command.get_current_dir().is_none() => {
+            if let Some(home) = dirs::home_dir() {
+                command.current_dir(home);
+            }
+        }
+        None => {}
+    }
+
+    for (name, value) in parameters.env.iter().flatten() {
+        if name.is_empty() || name.contains(['=', '\0']) {
+            log::error!("Invalid environment variable name: {:?}", name);
+            return Err(format!("Invalid environment variable name: {:?}", name));
+        }
+        command.env(name, value);
+    }
+
+    Ok(())
+}
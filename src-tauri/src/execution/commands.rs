@@ -0,0 +1,35 @@
+use serde_json::Value;
+use tauri::WebviewWindow;
+
+use super::desktop_entry::{installed_applications, InstalledApplication};
+use super::popup::Popups;
+use super::process_supervisor::{ProcessSupervisor, RunningAction};
+
+#[tauri::command]
+pub async fn list_installed_applications() -> Result<Vec<InstalledApplication>, String> {
+    Ok(installed_applications())
+}
+
+#[tauri::command]
+pub async fn list_running_actions() -> Result<Vec<RunningAction>, String> {
+    Ok(ProcessSupervisor::global().running_actions())
+}
+
+/// Cancels the running actions of a shortcut, or only the process `pid`.
+#[tauri::command]
+pub async fn cancel_running_action(shortcut_id: String, pid: Option<u32>) -> Result<usize, String> {
+    ProcessSupervisor::global().cancel(&shortcut_id, pid)
+}
+
+/// What the popup window calling it asks, see `Popups::ask`.
+#[tauri::command]
+pub async fn get_popup_request(window: WebviewWindow) -> Result<Value, String> {
+    Popups::global().request(window.label())
+}
+
+/// Answers the popup window calling it, `None` dismisses it.
+#[tauri::command]
+pub async fn answer_popup(window: WebviewWindow, answer: Option<Value>) -> Result<(), String> {
+    Popups::global().answer(window.label(), answer);
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime};
+use regex::Regex;
+
+use super::execution_shortcut::ActionResult;
+use super::path_resolver::resolve_path;
+use super::process_lookup::find_processes;
+use super::template::{render, Encoding, VariableResolver};
+use crate::definition::action::{ActionCondition, Weekday};
+
+/// Everything a condition may look at besides the previous step. Keeping it
+/// behind a trait lets the evaluation run against a fake system.
+pub trait ConditionProbe {
+    fn now(&self) -> DateTime<Local>;
+    fn path_exists(&self, path: &str) -> Result<bool, String>;
+    fn process_running(&self, name: &str) -> bool;
+    fn focused_app(&self) -> Option<String>;
+}
+
+/// Whether the condition holds. `previous` is the last step that ran; without
+/// one the exit code and stdout conditions don't hold.
+pub fn evaluate(
+    condition: &ActionCondition,
+    previous: Option<&ActionResult>,
+    probe: &dyn ConditionProbe,
+) -> Result<bool, String> {
+    match condition {
+        ActionCondition::ExitCode { equals } => {
+            Ok(previous.and_then(|previous| previous.exit_code) == Some(*equals))
+        }
+        ActionCondition::StdoutMatches { pattern } => {
+            let regex = Regex::new(pattern)
+                .map_err(|e| format!("Invalid stdout pattern {}: {}", pattern, e))?;
+            Ok(previous
+                .and_then(|previous| previous.stdout.as_deref())
+                .is_some_and(|stdout| regex.is_match(stdout)))
+        }
+        ActionCondition::FileExists { path } => probe.path_exists(path),
+        ActionCondition::ProcessRunning { name } => Ok(probe.process_running(name)),
+        ActionCondition::TimeOfDay { from, to } => {
+            let (from, to) = (parse_time(from)?, parse_time(to)?);
+            let now = probe.now().time();
+            Ok(if from <= to {
+                from <= now && now < to
+            } else {
+                // e.g. 22:00 to 06:00
+                now >= from || now < to
+            })
+        }
+        ActionCondition::Weekday { days } => {
+            let today = weekday(probe.now().weekday());
+            Ok(days.contains(&today))
+        }
+        ActionCondition::FocusedApp { app_name } => Ok(probe
+            .focused_app()
+            .is_some_and(|focused| focused.eq_ignore_ascii_case(app_name))),
+    }
+}
+
+fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time {}, expected HH:MM", time))
+}
+
+fn weekday(weekday: chrono::Weekday) -> Weekday {
+    match weekday {
+        chrono::Weekday::Mon => Weekday::Monday,
+        chrono::Weekday::Tue => Weekday::Tuesday,
+        chrono::Weekday::Wed => Weekday::Wednesday,
+        chrono::Weekday::Thu => Weekday::Thursday,
+        chrono::Weekday::Fri => Weekday::Friday,
+        chrono::Weekday::Sat => Weekday::Saturday,
+        chrono::Weekday::Sun => Weekday::Sunday,
+    }
+}
+
+/// The real system. Paths may contain template variables, and the focused
+/// app is the one captured when the shortcut was pressed.
+pub struct SystemProbe<'a> {
+    variables: &'a dyn VariableResolver,
+}
+
+impl<'a> SystemProbe<'a> {
+    pub fn new(variables: &'a dyn VariableResolver) -> Self {
+        Self { variables }
+    }
+}
+
+impl ConditionProbe for SystemProbe<'_> {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn path_exists(&self, path: &str) -> Result<bool, String> {
+        let path = render(path, self.variables, Encoding::Raw)?;
+        Ok(resolve_path(&path, None)?.exists())
+    }
+
+    fn process_running(&self, name: &str) -> bool {
+        !find_processes(name).is_empty()
+    }
+
+    fn focused_app(&self) -> Option<String> {
+        self.variables.resolve("window.app", None).ok().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::definition::action::ActionType;
+
+    struct FakeProbe {
+        now: DateTime<Local>,
+        focused_app: Option<String>,
+    }
+
+    impl FakeProbe {
+        /// 1 January 2024 was a Monday.
+        fn at(hour: u32, minute: u32) -> Self {
+            Self {
+                now: Local.with_ymd_and_hms(2024, 1, 1, hour, minute, 0).unwrap(),
+                focused_app: None,
+            }
+        }
+    }
+
+    impl ConditionProbe for FakeProbe {
+        fn now(&self) -> DateTime<Local> {
+            self.now
+        }
+
+        fn path_exists(&self, _path: &str) -> Result<bool, String> {
+            Ok(false)
+        }
+
+        fn process_running(&self, _name: &str) -> bool {
+            false
+        }
+
+        fn focused_app(&self) -> Option<String> {
+            self.focused_app.clone()
+        }
+    }
+
+    fn previous(exit_code: i32, stdout: &str) -> ActionResult {
+        let mut result = ActionResult::new(ActionType::RunShellScript, exit_code == 0, None);
+        result.exit_code = Some(exit_code);
+        result.stdout = Some(stdout.to_string());
+        result
+    }
+
+    fn time_of_day(from: &str, to: &str) -> ActionCondition {
+        ActionCondition::TimeOfDay {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn exit_code_compares_the_previous_step() {
+        let condition = ActionCondition::ExitCode { equals: 1 };
+        let probe = FakeProbe::at(12, 0);
+
+        assert!(evaluate(&condition, Some(&previous(1, "")), &probe).unwrap());
+        assert!(!evaluate(&condition, Some(&previous(0, "")), &probe).unwrap());
+        assert!(!evaluate(&condition, None, &probe).unwrap());
+    }
+
+    #[test]
+    fn stdout_matches_the_pattern() {
+        let condition = ActionCondition::StdoutMatches {
+            pattern: "^branch: (main|master)$".to_string(),
+        };
+        let probe = FakeProbe::at(12, 0);
+
+        assert!(evaluate(&condition, Some(&previous(0, "branch: main")), &probe).unwrap());
+        assert!(!evaluate(&condition, Some(&previous(0, "branch: dev")), &probe).unwrap());
+        assert!(!evaluate(&condition, None, &probe).unwrap());
+    }
+
+    #[test]
+    fn stdout_pattern_must_be_valid() {
+        let condition = ActionCondition::StdoutMatches {
+            pattern: "(".to_string(),
+        };
+
+        assert!(evaluate(&condition, Some(&previous(0, "")), &FakeProbe::at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn time_of_day_includes_start_and_excludes_end() {
+        let condition = time_of_day("09:00", "17:00");
+
+        assert!(evaluate(&condition, None, &FakeProbe::at(9, 0)).unwrap());
+        assert!(evaluate(&condition, None, &FakeProbe::at(16, 59)).unwrap());
+        assert!(!evaluate(&condition, None, &FakeProbe::at(17, 0)).unwrap());
+        assert!(!evaluate(&condition, None, &FakeProbe::at(8, 59)).unwrap());
+    }
+
+    #[test]
+    fn time_of_day_wraps_past_midnight() {
+        let condition = time_of_day("22:00", "06:00");
+
+        assert!(evaluate(&condition, None, &FakeProbe::at(23, 30)).unwrap());
+        assert!(evaluate(&condition, None, &FakeProbe::at(0, 0)).unwrap());
+        assert!(evaluate(&condition, None, &FakeProbe::at(5, 59)).unwrap());
+        assert!(!evaluate(&condition, None, &FakeProbe::at(6, 0)).unwrap());
+        assert!(!evaluate(&condition, None, &FakeProbe::at(12, 0)).unwrap());
+    }
+
+    #[test]
+    fn time_of_day_rejects_invalid_times() {
+        let condition = time_of_day("9am", "17:00");
+
+        assert!(evaluate(&condition, None, &FakeProbe::at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn weekday_matches_today() {
+        let probe = FakeProbe::at(12, 0);
+        let weekdays = ActionCondition::Weekday {
+            days: vec![Weekday::Monday, Weekday::Friday],
+        };
+        let weekend = ActionCondition::Weekday {
+            days: vec![Weekday::Saturday, Weekday::Sunday],
+        };
+
+        assert!(evaluate(&weekdays, None, &probe).unwrap());
+        assert!(!evaluate(&weekend, None, &probe).unwrap());
+    }
+
+    #[test]
+    fn focused_app_ignores_case() {
+        let condition = ActionCondition::FocusedApp {
+            app_name: "firefox".to_string(),
+        };
+        let mut probe = FakeProbe::at(12, 0);
+
+        assert!(!evaluate(&condition, None, &probe).unwrap());
+
+        probe.focused_app = Some("Firefox".to_string());
+        assert!(evaluate(&condition, None, &probe).unwrap());
+
+        probe.focused_app = Some("Code".to_string());
+        assert!(!evaluate(&condition, None, &probe).unwrap());
+    }
+}
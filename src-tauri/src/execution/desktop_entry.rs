@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+
+const ICON_SIZES: [&str; 6] = ["48x48", "64x64", "128x128", "scalable", "256x256", "32x32"];
+const MAX_ICON_BYTES: u64 = 256 * 1024;
+const TERMINALS: [(&str, &str); 7] = [
+    ("x-terminal-emulator", "-e"),
+    ("gnome-terminal", "--"),
+    ("konsole", "-e"),
+    ("xfce4-terminal", "-x"),
+    ("alacritty", "-e"),
+    ("kitty", ""),
+    ("xterm", "-e"),
+];
+
+/// An application entry from a `.desktop` file.
+#[derive(Debug, Clone)]
+struct DesktopEntry {
+    /// Desktop file id, e.g. `org.gnome.Nautilus.desktop`.
+    id: String,
+    name: String,
+    exec: String,
+    icon: Option<String>,
+    terminal: bool,
+    working_dir: Option<String>,
+    startup_wm_class: Option<String>,
+    no_display: bool,
+    file: PathBuf,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledApplication {
+    pub id: String,
+    pub name: String,
+    /// Icon as a data URL, when a PNG or SVG icon could be found.
+    pub icon: Option<String>,
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, in order of precedence.
+fn data_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var("XDG_DATA_HOME")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".local/share")));
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    data_home
+        .into_iter()
+        .chain(data_dirs.split(':').map(PathBuf::from))
+        .collect()
+}
+
+/// All application entries, keyed by desktop id. An entry in a directory with
+/// higher precedence hides entries with the same id further down the list.
+fn desktop_entries() -> Vec<DesktopEntry> {
+    let mut entries: HashMap<String, DesktopEntry> = HashMap::new();
+
+    for data_dir in data_dirs() {
+        let applications = data_dir.join("applications");
+        for file in desktop_files(&applications) {
+            let Some(id) = desktop_id(&applications, &file) else {
+                continue;
+            };
+            if entries.contains_key(&id) {
+                continue;
+            }
+            // Hidden entries still hide the ones they shadow.
+            if let Some(entry) = parse_desktop_file(&id, &file) {
+                entries.insert(id, entry);
+            }
+        }
+    }
+
+    let mut entries: Vec<DesktopEntry> = entries
+        .into_values()
+        .filter(|entry| !entry.exec.is_empty())
+        .collect();
+    entries.sort_by_key(|entry| entry.name.to_lowercase());
+    entries
+}
+
+fn desktop_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    read_dir
+        .filter_map(Result::ok)
+        .flat_map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                desktop_files(&path)
+            } else if path.extension().is_some_and(|ext| ext == "desktop") {
+                vec![path]
+            } else {
+                Vec::new()
+            }
+        })
+        .collect()
+}
+
+/// Path relative to the applications directory with `/` replaced by `-`.
+fn desktop_id(applications: &Path, file: &Path) -> Option<String> {
+    let relative = file.strip_prefix(applications).ok()?;
+    Some(relative.to_string_lossy().replace('/', "-"))
+}
+
+fn parse_desktop_file(id: &str, file: &Path) -> Option<DesktopEntry> {
+    let content = fs::read_to_string(file).ok()?;
+    let mut in_main_group = false;
+    let mut values: HashMap<&str, &str> = HashMap::new();
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_main_group = line == "[Desktop Entry]";
+            continue;
+        }
+        if !in_main_group || line.starts_with('#') {
+            continue;
+        }
+        // Localized keys (Name[de]) are skipped, the untranslated value is used.
+        if let Some((key, value)) = line.split_once('=') {
+            values.entry(key.trim()).or_insert(value.trim());
+        }
+    }
+
+    let is_true = |key: &str| values.get(key).is_some_and(|value| *value == "true");
+    if values.get("Type") != Some(&"Application") || is_true("Hidden") {
+        return Some(DesktopEntry {
+            id: id.to_string(),
+            name: String::new(),
+            exec: String::new(),
+            icon: None,
+            terminal: false,
+            working_dir: None,
+            startup_wm_class: None,
+            no_display: true,
+            file: file.to_path_buf(),
+        });
+    }
+
+    Some(DesktopEntry {
+        id: id.to_string(),
+        name: unescape_value(values.get("Name").copied().unwrap_or(id)),
+        exec: unescape_value(values.get("Exec").copied().unwrap_or_default()),
+        icon: values.get("Icon").map(|icon| unescape_value(icon)),
+        terminal: is_true("Terminal"),
+        working_dir: values.get("Path").map(|path| unescape_value(path)),
+        startup_wm_class: values
+            .get("StartupWMClass")
+            .map(|class| unescape_value(class)),
+        no_display: is_true("NoDisplay"),
+        file: file.to_path_buf(),
+    })
+}
+
+/// Resolves the escape sequences of desktop entry string values.
+fn unescape_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                // Left for the quoting rules of `exec_arguments`.
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Finds the entry for `app_name`, matching (case-insensitively) the desktop id
+/// with or without `.desktop`, the Name, or the program in Exec.
+fn find_desktop_entry(app_name: &str) -> Option<DesktopEntry> {
+    let wanted = app_name.trim().to_lowercase();
+    let entries = desktop_entries();
+
+    let by_id = |entry: &&DesktopEntry| {
+        let id = entry.id.to_lowercase();
+        id == wanted || id.strip_suffix(".desktop") == Some(wanted.as_str())
+    };
+    let by_name = |entry: &&DesktopEntry| entry.name.to_lowercase() == wanted;
+    let by_exec = |entry: &&DesktopEntry| {
+        exec_program(&entry.exec).is_some_and(|program| program.to_lowercase() == wanted)
+    };
+
+    entries
+        .iter()
+        .find(by_id)
+        .or_else(|| entries.iter().find(by_name))
+        .or_else(|| entries.iter().find(by_exec))
+        .cloned()
+}
+
+/// File name of the program an Exec value runs.
+fn exec_program(exec: &str) -> Option<String> {
+    let program = exec_arguments(exec).ok()?.into_iter().next()?;
+    Some(program.rsplit('/').next().unwrap_or(&program).to_string())
+}
+
+/// Splits an Exec value into arguments following the quoting rules of the
+/// desktop entry specification.
+fn exec_arguments(exec: &str) -> Result<Vec<String>, String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut chars = exec.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_argument = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '`' | '$' | '\\')) => current.push(escaped),
+                            Some(other) => {
+                                current.push('\\');
+                                current.push(other);
+                            }
+                            None => return Err(format!("Unterminated quote in Exec: {}", exec)),
+                        },
+                        Some(other) => current.push(other),
+                        None => return Err(format!("Unterminated quote in Exec: {}", exec)),
+                    }
+                }
+            }
+            ' ' | '\t' => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            '\\' => {
+                in_argument = true;
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            other => {
+                in_argument = true;
+                current.push(other);
+            }
+        }
+    }
+
+    if in_argument {
+        arguments.push(current);
+    }
+    Ok(arguments)
+}
+
+/// Builds the command line for the entry, expanding Exec field codes. `args`
+/// all go in place of the first `%f`/`%F`/`%u`/`%U`, whichever it is, since
+/// they may be options as well as files; they are appended when Exec has no
+/// such field code.
+fn entry_command(entry: &DesktopEntry, args: &[String]) -> Result<Command, String> {
+    let mut arguments = Vec::new();
+    let mut args_placed = false;
+
+    for argument in exec_arguments(&entry.exec)? {
+        match argument.as_str() {
+            "%f" | "%F" | "%u" | "%U" => {
+                if !args_placed {
+                    arguments.extend(args.iter().cloned());
+                    args_placed = true;
+                }
+            }
+            "%i" => {
+                if let Some(icon) = &entry.icon {
+                    arguments.push("--icon".to_string());
+                    arguments.push(icon.clone());
+                }
+            }
+            _ => {
+                // An embedded code, as in `--url=%u`, takes the first arg, the
+                // others follow it.
+                let takes_args = !args_placed
+                    && ["%f", "%F", "%u", "%U"]
+                        .iter()
+                        .any(|code| argument.contains(code));
+                let first = args.first().filter(|_| takes_args);
+                let expanded = expand_field_codes(&argument, entry, first);
+                if !expanded.is_empty() || !argument.contains('%') {
+                    arguments.push(expanded);
+                }
+                if takes_args {
+                    arguments.extend(args.iter().skip(1).cloned());
+                    args_placed = true;
+                }
+            }
+        }
+    }
+
+    if !args_placed {
+        arguments.extend(args.iter().cloned());
+    }
+    if entry.terminal {
+        arguments = terminal_command(arguments)?;
+    }
+
+    let Some((program, arguments)) = arguments.split_first() else {
+        log::error!("Empty Exec in {}", entry.file.display());
+        return Err(format!("Empty Exec in {}", entry.file.display()));
+    };
+
+    let mut command = Command::new(program);
+    command.args(arguments);
+    if let Some(dir) = entry.working_dir.as_deref().filter(|dir| !dir.is_empty()) {
+        command.current_dir(dir);
+    }
+    Ok(command)
+}
+
+/// Field codes embedded in a longer argument; deprecated codes are dropped.
+fn expand_field_codes(argument: &str, entry: &DesktopEntry, file: Option<&String>) -> String {
+    let mut expanded = String::with_capacity(argument.len());
+    let mut chars = argument.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => expanded.push('%'),
+            Some('f' | 'F' | 'u' | 'U') => expanded.push_str(file.map_or("", String::as_str)),
+            Some('c') => expanded.push_str(&entry.name),
+            Some('k') => expanded.push_str(&entry.file.to_string_lossy()),
+            _ => {}
+        }
+    }
+
+    expanded
+}
+
+/// Wraps the command in a terminal emulator: `$TERMINAL` when set, otherwise
+/// the first known terminal found on PATH.
+fn terminal_command(arguments: Vec<String>) -> Result<Vec<String>, String> {
+    if let Some(terminal) = std::env::var("TERMINAL")
+        .ok()
+        .filter(|terminal| !terminal.is_empty())
+    {
+        return Ok([terminal, "-e".to_string()]
+            .into_iter()
+            .chain(arguments)
+            .collect());
+    }
+
+    let Some((terminal, flag)) = TERMINALS.iter().find(|(terminal, _)| on_path(terminal)) else {
+        log::error!("No terminal emulator found for Terminal=true entry");
+        return Err("No terminal emulator found for Terminal=true entry".to_string());
+    };
+
+    Ok([terminal.to_string(), flag.to_string()]
+        .into_iter()
+        .filter(|argument| !argument.is_empty())
+        .chain(arguments)
+        .collect())
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Command that launches `app_name` with `args`: through its desktop entry when
+/// there is one, otherwise as a program on PATH. No shell is involved.
+pub fn application_command(app_name: &str, args: &[String]) -> Result<Command, String> {
+    match find_desktop_entry(app_name) {
+        Some(entry) => {
+            log::info!("Launching {} from {}", app_name, entry.file.display());
+            entry_command(&entry, args)
+        }
+        None => {
+            let mut command = Command::new(app_name);
+            command.args(args);
+            Ok(command)
+        }
+    }
+}
+
+/// Names the windows and processes of `app_name` go by: the StartupWMClass and
+/// Exec program of its desktop entry, then `app_name` itself without
+/// `.desktop`. The picker saves desktop ids, which no window or process has.
+pub fn application_names(app_name: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(entry) = find_desktop_entry(app_name) {
+        names.extend(entry.startup_wm_class.filter(|class| !class.is_empty()));
+        names.extend(exec_program(&entry.exec));
+    }
+    let app_name = app_name.trim();
+    names.push(
+        app_name
+            .strip_suffix(".desktop")
+            .unwrap_or(app_name)
+            .to_string(),
+    );
+
+    let mut unique: Vec<String> = Vec::new();
+    for name in names {
+        if !unique.iter().any(|seen| seen.eq_ignore_ascii_case(&name)) {
+            unique.push(name);
+        }
+    }
+    unique
+}
+
+/// Visible applications for the picker.
+pub fn installed_applications() -> Vec<InstalledApplication> {
+    let data_dirs = data_dirs();
+
+    desktop_entries()
+        .into_iter()
+        .filter(|entry| !entry.no_display)
+        .map(|entry| InstalledApplication {
+            icon: entry
+                .icon
+                .as_deref()
+                .and_then(|icon| icon_data_url(icon, &data_dirs)),
+            id: entry.id,
+            name: entry.name,
+        })
+        .collect()
+}
+
+/// Looks the icon up in the hicolor theme and pixmaps, and inlines it so the
+/// webview doesn't need file system access to show it.
+fn icon_data_url(icon: &str, data_dirs: &[PathBuf]) -> Option<String> {
+    let candidates: Vec<PathBuf> = if Path::new(icon).is_absolute() {
+        vec![PathBuf::from(icon)]
+    } else {
+        data_dirs
+            .iter()
+            .flat_map(|data_dir| {
+                ICON_SIZES
+                    .iter()
+                    .map(move |size| data_dir.join("icons/hicolor").join(size).join("apps"))
+                    .chain(std::iter::once(data_dir.join("pixmaps")))
+            })
+            .flat_map(|dir| ["png", "svg"].map(|ext| dir.join(format!("{}.{}", icon, ext))))
+            .collect()
+    };
+
+    let file = candidates.into_iter().find(|file| {
+        file.metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.len() <= MAX_ICON_BYTES)
+    })?;
+    let mime = match file.extension()?.to_str()? {
+        "png" => "image/png",
+        "svg" => "image/svg+xml",
+        _ => return None,
+    };
+
+    let data = fs::read(&file).ok()?;
+    Some(format!("data:{};base64,{}", mime, STANDARD.encode(data)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(exec: &str) -> DesktopEntry {
+        DesktopEntry {
+            id: "test.desktop".to_string(),
+            name: "Test".to_string(),
+            exec: exec.to_string(),
+            icon: None,
+            terminal: false,
+            working_dir: None,
+            startup_wm_class: None,
+            no_display: false,
+            file: PathBuf::from("/usr/share/applications/test.desktop"),
+        }
+    }
+
+    fn command_line(exec: &str, args: &[&str]) -> Vec<String> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let command = entry_command(&entry(exec), &args).unwrap();
+        std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn single_url_code_takes_every_arg() {
+        assert_eq!(
+            command_line("firefox %u", &["--private-window", "https://x"]),
+            ["firefox", "--private-window", "https://x"]
+        );
+    }
+
+    #[test]
+    fn url_list_code_takes_every_arg() {
+        assert_eq!(
+            command_line("code --new-window %U --wait", &["a.txt", "b.txt"]),
+            ["code", "--new-window", "a.txt", "b.txt", "--wait"]
+        );
+    }
+
+    #[test]
+    fn args_are_appended_without_field_code() {
+        assert_eq!(
+            command_line("gimp", &["-n", "image.png"]),
+            ["gimp", "-n", "image.png"]
+        );
+    }
+
+    #[test]
+    fn field_codes_without_args_are_dropped() {
+        assert_eq!(command_line("evince %f", &[]), ["evince"]);
+    }
+
+    #[test]
+    fn embedded_code_takes_the_first_arg() {
+        assert_eq!(
+            command_line("app --open=%f", &["a.txt", "b.txt"]),
+            ["app", "--open=a.txt", "b.txt"]
+        );
+    }
+
+    #[test]
+    fn args_are_placed_once() {
+        assert_eq!(
+            command_line("app %f %F", &["a.txt", "b.txt"]),
+            ["app", "a.txt", "b.txt"]
+        );
+    }
+}
@@ -7,17 +7,78 @@ use tauri_plugin_global_shortcut::{
     Code, GlobalShortcutExt, Modifiers, Shortcut as TauriShortcut, ShortcutState,
 };
 
-use super::execution_shortcut::{ExecutionAction, ExecutionShortcut};
+use super::clipboard::write_clipboard;
+use super::command_options::apply_command_options;
+use super::condition::{evaluate, SystemProbe};
+use super::desktop_entry::application_command;
+use super::execution_history::{record_execution, Confirmation, HistoryEntry};
+use super::execution_shortcut::{
+    ActionResult, ExecutionAction, ExecutionShortcut, ShortcutEventPayload,
+};
+use super::file_operations::{copy_path, move_path, rename_path};
+use super::key_injector::{default_key_injector, parse_key_sequence, send_key_combos, type_text};
+use super::path_resolver::{expand_path, resolve_path, resolve_paths};
+use super::popup::{
+    ChoiceOption, ChoiceRequest, ConfirmRequest, PopupWindow, Popups, PromptRequest,
+};
+use super::process_supervisor::{
+    ProcessOrigin, ProcessRun, ProcessSupervisor, RunLimits, SupervisedProcess,
+};
+use super::script_command::script_command;
+use super::script_limits::apply_script_limits;
+use super::state_store::StateStore;
+use super::template::{
+    render_parameters, NestedShortcutVariables, PreviousStepVariables, PromptVariables,
+    ShortcutVariables, StateVariables, VariableResolver,
+};
+use super::url_opener::{open_url_command, validate_url};
+use super::window_manager::{apply_window_action, toggle_application, ToggleOutcome};
+use super::x11_window_manager::X11WindowManager;
 use crate::config::AppConfig;
-use crate::definition::action::ActionType;
-use dirs;
+use crate::definition::action::{ActionType, JoinPolicy, OnFailure, OverwritePolicy};
+use crate::definition::shortcut::ExecutionMode;
+use chrono::Local;
+use std::collections::HashMap;
 use std::process::Command;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+const MAX_SHORTCUT_DEPTH: usize = 8;
+const DEFAULT_TOGGLE_KEY: &str = "toggle";
+const DEFAULT_CONFIRMATION_TIMEOUT_SECS: u64 = 30;
 
 pub struct ExecutionFacade<R: Runtime> {
     app_handle: AppHandle<R>,
     shortcut_cache: Vec<ExecutionShortcut>,
 }
 
+/// What an action produced. `run` is the process it waited for, if any.
+#[derive(Default)]
+struct ActionOutput {
+    message: Option<String>,
+    run: Option<ProcessRun>,
+}
+
+impl ActionOutput {
+    fn message(message: String) -> Self {
+        Self {
+            message: Some(message),
+            run: None,
+        }
+    }
+}
+
+impl<R: Runtime> Clone for ExecutionFacade<R> {
+    fn clone(&self) -> Self {
+        Self {
+            app_handle: self.app_handle.clone(),
+            shortcut_cache: self.shortcut_cache.clone(),
+        }
+    }
+}
+
 impl<R: Runtime> ExecutionFacade<R> {
     pub fn new(app_handle: AppHandle<R>) -> Self {
         let shortcuts = match Self::load_shortcuts_from_file() {
@@ -179,106 +240,964 @@ impl<R: Runtime> ExecutionFacade<R> {
         shortcut: &TauriShortcut,
         state: ShortcutState,
     ) -> Result<(), String> {
+        let Some(execution_shortcut) = self.shortcut_cache.iter().find(|s| {
+            if let Some(parsed_shortcut) = self.parse_shortcut(&s.key_combination) {
+                &parsed_shortcut == shortcut
+            } else {
+                false
+            }
+        }) else {
+            log::error!("No matching shortcut found in cache");
+            return Err("No matching shortcut found in cache".to_string());
+        };
+
         match state {
             ShortcutState::Pressed => {
                 log::info!("Shortcut pressed: {:?}", shortcut);
+                log::info!("Shortcut: {:?}", execution_shortcut.key_combination);
 
-                if let Some(execution_shortcut) = self.shortcut_cache.iter().find(|s| {
-                    if let Some(parsed_shortcut) = self.parse_shortcut(&s.key_combination) {
-                        &parsed_shortcut == shortcut
-                    } else {
-                        false
-                    }
-                }) {
-                    log::info!("Shortcut: {:?}", execution_shortcut.key_combination);
-                    self.execute_actions(&execution_shortcut.actions)?;
-                } else {
-                    log::error!("No matching shortcut found in cache");
-                    return Err("No matching shortcut found in cache".to_string());
-                }
+                let variables: Arc<dyn VariableResolver> =
+                    Arc::new(ShortcutVariables::capture(&execution_shortcut.command_name));
+                let Some(results) = self.execute_shortcut(execution_shortcut, None, &variables)?
+                else {
+                    return self
+                        .app_handle
+                        .emit(
+                            "shortcut-triggered",
+                            ShortcutEventPayload::new(execution_shortcut, "Cancelled", Vec::new()),
+                        )
+                        .map_err(|e| e.to_string());
+                };
+                let failure = results
+                    .iter()
+                    .find(|result| !result.success)
+                    .and_then(|result| result.message.clone());
 
                 self.app_handle
-                    .emit("shortcut-triggered", "Shortcut Pressed!")
-                    .map_err(|e| e.to_string())
+                    .emit(
+                        "shortcut-triggered",
+                        ShortcutEventPayload::new(execution_shortcut, "Pressed", results),
+                    )
+                    .map_err(|e| e.to_string())?;
+
+                match failure {
+                    Some(error) => Err(error),
+                    None => Ok(()),
+                }
             }
             ShortcutState::Released => {
                 log::info!("Shortcut released: {:?}", shortcut);
                 self.app_handle
-                    .emit("shortcut-triggered", "Shortcut Released!")
+                    .emit(
+                        "shortcut-triggered",
+                        ShortcutEventPayload::new(execution_shortcut, "Released", Vec::new()),
+                    )
                     .map_err(|e| e.to_string())
             }
         }
     }
 
-    fn execute_actions(&self, actions: &[ExecutionAction]) -> Result<(), String> {
+    /// Runs a press of the shortcut, after the user confirmed it if it
+    /// requires confirmation, and records it in the execution history. `None`
+    /// when the user cancelled it before anything ran.
+    fn execute_shortcut(
+        &self,
+        shortcut: &ExecutionShortcut,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> Result<Option<Vec<ActionResult>>, String> {
+        let started_at = Local::now();
+        let confirmation = self.confirm(shortcut);
+        let outcome = match confirmation {
+            Ok(None | Some(Confirmation::Confirmed)) => {
+                self.execute_confirmed(shortcut, previous, variables)
+            }
+            Ok(Some(answer)) => {
+                log::info!(
+                    "{} not run, confirmation {:?}",
+                    shortcut.command_name,
+                    answer
+                );
+                Ok(None)
+            }
+            Err(ref error) => Err(error.clone()),
+        };
+
+        let entry = HistoryEntry::new(shortcut, started_at, &outcome, confirmation.ok().flatten());
+        if let Err(e) = record_execution(&entry) {
+            log::error!(
+                "Failed to record execution of {}: {}",
+                shortcut.command_name,
+                e
+            );
+        }
+        outcome
+    }
+
+    /// Asks the user to confirm running the shortcut, if it requires that.
+    /// Not answering within the timeout counts as cancelling.
+    fn confirm(&self, shortcut: &ExecutionShortcut) -> Result<Option<Confirmation>, String> {
+        if !shortcut.require_confirmation.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let timeout = Duration::from_secs(
+            shortcut
+                .confirmation_timeout_secs
+                .unwrap_or(DEFAULT_CONFIRMATION_TIMEOUT_SECS),
+        );
+        let window = PopupWindow {
+            page: "confirm",
+            title: &shortcut.command_name,
+            width: 360.0,
+            height: 200.0,
+        };
+        let request = ConfirmRequest {
+            shortcut_name: &shortcut.command_name,
+            key_combination: &shortcut.key_combination,
+            actions: shortcut
+                .actions
+                .iter()
+                .map(|action| &action.action_type)
+                .collect(),
+            timeout_secs: timeout.as_secs(),
+        };
+        let asked_at = Instant::now();
+        let confirmed: Option<bool> =
+            Popups::global().ask(&self.app_handle, window, &request, Some(timeout))?;
+
+        Ok(Some(match confirmed {
+            Some(true) => Confirmation::Confirmed,
+            Some(false) => Confirmation::Declined,
+            // Without an answer the popup was either closed or timed out.
+            None if asked_at.elapsed() >= timeout => Confirmation::TimedOut,
+            None => Confirmation::Declined,
+        }))
+    }
+
+    /// Runs a press of the shortcut: all of its actions, or in
+    /// `ExecutionMode::Cycle` only the next one in turn. `None` when the user
+    /// dismissed its prompt.
+    fn execute_confirmed(
+        &self,
+        shortcut: &ExecutionShortcut,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> Result<Option<Vec<ActionResult>>, String> {
+        let Some(variables) = self.prompt_variables(shortcut, variables)? else {
+            log::info!("Prompt of {} dismissed", shortcut.command_name);
+            return Ok(None);
+        };
+
+        if shortcut.execution_mode != Some(ExecutionMode::Cycle) || shortcut.actions.is_empty() {
+            return Ok(Some(self.execute_actions(
+                shortcut,
+                &shortcut.actions,
+                previous,
+                &variables,
+            )));
+        }
+
+        let index = StateStore::global().next_cycle_step(
+            &shortcut.id,
+            shortcut.actions.len(),
+            shortcut.cycle_reset_after_secs.map(Duration::from_secs),
+        );
+        log::info!(
+            "Cycling {} to action {} of {}",
+            shortcut.command_name,
+            index + 1,
+            shortcut.actions.len()
+        );
+        Ok(Some(self.execute_actions(
+            shortcut,
+            &shortcut.actions[index..=index],
+            previous,
+            &variables,
+        )))
+    }
+
+    /// Asks for the values of the shortcut's prompts, if it has any, and adds
+    /// them to the variables. `None` when the user dismissed the prompt.
+    fn prompt_variables(
+        &self,
+        shortcut: &ExecutionShortcut,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> Result<Option<Arc<dyn VariableResolver>>, String> {
+        let Some(prompts) = shortcut
+            .prompts
+            .as_deref()
+            .filter(|prompts| !prompts.is_empty())
+        else {
+            return Ok(Some(variables.clone()));
+        };
+
+        let window = PopupWindow {
+            page: "prompt",
+            title: &shortcut.command_name,
+            width: 420.0,
+            height: 120.0 + 72.0 * prompts.len() as f64,
+        };
+        let request = PromptRequest {
+            shortcut_name: &shortcut.command_name,
+            prompts,
+        };
+        let values: Option<HashMap<String, String>> =
+            Popups::global().ask(&self.app_handle, window, &request, None)?;
+
+        Ok(values.map(|values| {
+            Arc::new(PromptVariables::new(values, variables.clone())) as Arc<dyn VariableResolver>
+        }))
+    }
+
+    /// Runs the actions one after another. Each action finishes before the
+    /// next one starts (see `ExecutionAction::waits`), and a failed action is
+    /// retried, skipped over or stops the pipeline according to its
+    /// `on_failure`. Returns the result of every step that ran. `previous` is
+    /// the step before the first action, for its condition.
+    fn execute_actions(
+        &self,
+        shortcut: &ExecutionShortcut,
+        actions: &[ExecutionAction],
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> Vec<ActionResult> {
+        let mut results: Vec<ActionResult> = Vec::new();
+        let mut previous = previous.cloned();
+
         for action in actions {
-            match action.action_type {
-                ActionType::OpenFolder => {
-                    log::info!(
-                        "OpenFolder action: {:?} : {:?}",
+            let result = self.execute_step(shortcut, action, previous.as_ref(), variables);
+            let stop = !result.success && action.on_failure != Some(OnFailure::Continue);
+            if !result.skipped {
+                previous = Some(result.clone());
+            }
+            results.push(result);
+
+            if stop {
+                log::warn!("Stopping {} after a failed step", shortcut.command_name);
+                break;
+            }
+        }
+
+        results
+    }
+
+    /// Runs a single action if its condition holds (otherwise its else branch),
+    /// retrying it with backoff when its `on_failure` says so. Template
+    /// variables are substituted right before every attempt.
+    fn execute_step(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let started = Instant::now();
+
+        if let Some(condition) = &action.condition {
+            let state_variables = StateVariables::new(&shortcut.id, variables.as_ref());
+            match evaluate(condition, previous, &SystemProbe::new(&state_variables)) {
+                Ok(true) => {}
+                Ok(false) => return self.execute_else(shortcut, action, previous, variables),
+                Err(error) => {
+                    log::error!(
+                        "Invalid condition on {:?} action: {}",
                         action.action_type,
-                        action.parameters.path
+                        error
                     );
-                    if let Some(path) = &action.parameters.path {
-                        let expanded_path = if path.starts_with('~') {
-                            if let Some(home_dir) = dirs::home_dir() {
-                                home_dir.join(&path[2..]).to_string_lossy().to_string()
-                            } else {
-                                path.to_string()
-                            }
-                        } else {
-                            path.to_string()
-                        };
+                    let mut result =
+                        ActionResult::new(action.action_type.clone(), false, Some(error));
+                    result.duration_ms = started.elapsed().as_millis() as u64;
+                    return result;
+                }
+            }
+        }
 
-                        Command::new("xdg-open")
-                            .arg(&expanded_path)
-                            .spawn()
-                            .map_err(|e| {
-                                log::error!(
-                                    "Failed to execute xdg-open for {}: {}",
-                                    expanded_path,
-                                    e
-                                );
-                                e.to_string()
-                            })?;
-                    } else {
-                        log::error!("No path specified for OpenFolder action");
-                        return Err("No path specified for OpenFolder action".to_string());
-                    }
+        let (retries, mut backoff) = match action.on_failure {
+            Some(OnFailure::Retry { times, backoff_ms }) => (
+                times,
+                Duration::from_millis(backoff_ms.unwrap_or(DEFAULT_RETRY_BACKOFF_MS)),
+            ),
+            _ => (0, Duration::ZERO),
+        };
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let mut result = match action.action_type {
+                ActionType::Parallel => {
+                    self.execute_parallel(shortcut, action, previous, variables)
                 }
-                ActionType::OpenFile => {
-                    if let Some(path) = &action.parameters.path {
-                        Command::new("xdg-open")
-                            .arg(path)
-                            .spawn()
-                            .map_err(|e| e.to_string())?;
-                    } else {
-                        log::error!("No path specified for OpenFile action");
-                        return Err("No path specified for OpenFile action".to_string());
-                    }
+                ActionType::RunShortcut => {
+                    self.execute_shortcut_action(shortcut, action, previous, variables)
                 }
-                ActionType::OpenApplication => {
-                    if let Some(app_name) = &action.parameters.app_name {
-                        Command::new(app_name).spawn().map_err(|e| e.to_string())?;
-                    }
+                ActionType::Toggle => self.execute_toggle(shortcut, action, previous, variables),
+                ActionType::ChoiceMenu => {
+                    self.execute_choice_menu(shortcut, action, previous, variables)
+                }
+                _ => self.execute_single(shortcut, action, previous, variables),
+            };
+
+            if result.success || attempts > retries {
+                result.attempts = attempts;
+                result.duration_ms = started.elapsed().as_millis() as u64;
+                return result;
+            }
+
+            log::info!(
+                "Retrying {:?} action in {}ms ({} of {})",
+                action.action_type,
+                backoff.as_millis(),
+                attempts,
+                retries
+            );
+            thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+
+    /// Runs an action that isn't a group. Its parameters may use the output of
+    /// the previous step through `{prev.stdout}`, or take it as stdin.
+    fn execute_single(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let variables = StateVariables::new(&shortcut.id, variables.as_ref());
+        let variables = PreviousStepVariables::new(previous, &variables);
+        let outcome = render_parameters(&action.parameters, &variables).and_then(|parameters| {
+            self.execute_action(
+                shortcut,
+                &ExecutionAction {
+                    parameters,
+                    ..action.clone()
+                },
+                previous,
+            )
+        });
+
+        let output = match outcome {
+            Ok(output) => output,
+            Err(error) => {
+                log::error!("{:?} action failed: {}", action.action_type, error);
+                return ActionResult::new(action.action_type.clone(), false, Some(error));
+            }
+        };
+
+        let Some(run) = output.run else {
+            return ActionResult::new(action.action_type.clone(), true, output.message);
+        };
+        let mut result = match run.failure_message() {
+            Some(error) => {
+                log::error!("{:?} action failed: {}", action.action_type, error);
+                ActionResult::new(action.action_type.clone(), false, Some(error))
+            }
+            None => ActionResult::new(action.action_type.clone(), true, output.message),
+        };
+        result.exit_code = run.exit_code;
+        result.stdout = Some(run.stdout);
+        result
+    }
+
+    /// Runs the else branch of an action whose condition doesn't hold, or
+    /// skips the action when there is none.
+    fn execute_else(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let else_actions = action.else_actions.as_deref().unwrap_or_default();
+        if else_actions.is_empty() {
+            log::info!(
+                "Condition not met, skipping {:?} action",
+                action.action_type
+            );
+            let mut result = ActionResult::new(
+                action.action_type.clone(),
+                true,
+                Some("Condition not met".to_string()),
+            );
+            result.skipped = true;
+            return result;
+        }
+
+        log::info!(
+            "Condition not met, running the else branch of {:?} action",
+            action.action_type
+        );
+        let started = Instant::now();
+        let steps = self.execute_actions(shortcut, else_actions, previous, variables);
+
+        let mut result = steps_result(
+            action.action_type.clone(),
+            "Condition not met, ran the else branch".to_string(),
+            steps,
+        );
+        result.duration_ms = started.elapsed().as_millis() as u64;
+        result
+    }
+
+    /// Runs the actions of another shortcut in place of the action. Cycles are
+    /// rejected when shortcuts are saved, the depth limit catches whatever slips
+    /// through, like a hand-edited shortcuts file. The target runs as a step of
+    /// this shortcut: only its prompts are asked, it isn't confirmed, cycled or
+    /// recorded on its own.
+    fn execute_shortcut_action(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let failure = |error: String| {
+            log::error!("RunShortcut action failed: {}", error);
+            ActionResult::new(ActionType::RunShortcut, false, Some(error))
+        };
+
+        let Some(id) = action.parameters.shortcut_id.as_deref() else {
+            return failure("No shortcut_id specified for RunShortcut action".to_string());
+        };
+        let Some(target) = self.shortcut_cache.iter().find(|cached| cached.id == id) else {
+            return failure(format!("Shortcut {} not found", id));
+        };
+        if shortcut.depth >= MAX_SHORTCUT_DEPTH {
+            return failure(format!(
+                "Shortcuts nested more than {} levels deep, not running {}",
+                MAX_SHORTCUT_DEPTH, target.command_name
+            ));
+        }
+
+        let mut target = target.clone();
+        target.depth = shortcut.depth + 1;
+        log::info!("Running shortcut {}", target.command_name);
+
+        let variables: Arc<dyn VariableResolver> = Arc::new(NestedShortcutVariables::new(
+            &target.command_name,
+            variables.clone(),
+        ));
+        let variables = match self.prompt_variables(&target, &variables) {
+            Ok(Some(variables)) => variables,
+            Ok(None) => return failure(format!("{} was cancelled", target.command_name)),
+            Err(error) => return failure(error),
+        };
+        let steps = self.execute_actions(&target, &target.actions, previous, &variables);
+        steps_result(
+            ActionType::RunShortcut,
+            format!("Ran {}", target.command_name),
+            steps,
+        )
+    }
+
+    /// Runs the toggle's `actions` on odd presses and its `alternate_actions`
+    /// on even ones. Whether it is on is kept in the state under `state_key`,
+    /// and flipped back when the actions fail, so the next press tries them
+    /// again.
+    fn execute_toggle(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let failure = |error: String| {
+            log::error!("Toggle action failed: {}", error);
+            ActionResult::new(ActionType::Toggle, false, Some(error))
+        };
+
+        if action.actions.as_deref().unwrap_or_default().is_empty() {
+            return failure("No actions specified for Toggle action".to_string());
+        }
+        let key = action
+            .parameters
+            .state_key
+            .as_deref()
+            .unwrap_or(DEFAULT_TOGGLE_KEY);
+        let scope = action.parameters.state_scope.unwrap_or_default();
+        let store = StateStore::global();
+        let on = match store.flip(&shortcut.id, scope, key) {
+            Ok(on) => on,
+            Err(error) => return failure(error),
+        };
+
+        let actions = if on {
+            &action.actions
+        } else {
+            &action.alternate_actions
+        };
+        let steps = self.execute_actions(
+            shortcut,
+            actions.as_deref().unwrap_or_default(),
+            previous,
+            variables,
+        );
+        let result = steps_result(
+            ActionType::Toggle,
+            format!("Turned {} {}", key, if on { "on" } else { "off" }),
+            steps,
+        );
+
+        if !result.success {
+            if let Err(error) = store.flip(&shortcut.id, scope, key) {
+                log::error!("Failed to flip {} back: {}", key, error);
+            }
+        }
+        result
+    }
+
+    /// Shows the menu's choices in a popup and runs the actions of the one the
+    /// user picks. Dismissing the menu skips the action.
+    fn execute_choice_menu(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let failure = |error: String| {
+            log::error!("ChoiceMenu action failed: {}", error);
+            ActionResult::new(ActionType::ChoiceMenu, false, Some(error))
+        };
+
+        let Some(choices) = action
+            .choices
+            .as_deref()
+            .filter(|choices| !choices.is_empty())
+        else {
+            return failure("No choices specified for ChoiceMenu action".to_string());
+        };
+
+        let window = PopupWindow {
+            page: "choice",
+            title: &shortcut.command_name,
+            width: 320.0,
+            height: 80.0 + 44.0 * choices.len() as f64,
+        };
+        let request = ChoiceRequest {
+            title: &shortcut.command_name,
+            choices: choices
+                .iter()
+                .map(|choice| ChoiceOption {
+                    name: &choice.name,
+                    key: choice.key.as_deref(),
+                })
+                .collect(),
+        };
+        let index: Option<usize> =
+            match Popups::global().ask(&self.app_handle, window, &request, None) {
+                Ok(index) => index,
+                Err(error) => return failure(error),
+            };
+
+        let Some(index) = index else {
+            log::info!("Menu of {} dismissed", shortcut.command_name);
+            let mut result = ActionResult::new(
+                ActionType::ChoiceMenu,
+                true,
+                Some("Nothing chosen".to_string()),
+            );
+            result.skipped = true;
+            return result;
+        };
+        let Some(choice) = choices.get(index) else {
+            return failure(format!("Menu has no choice {}", index));
+        };
+
+        log::info!("Chose {} from the menu", choice.name);
+        let steps = self.execute_actions(shortcut, &choice.actions, previous, variables);
+        steps_result(
+            ActionType::ChoiceMenu,
+            format!("Chose {}", choice.name),
+            steps,
+        )
+    }
+
+    /// Runs the group's actions at the same time, each on its own thread and
+    /// with its own failure policy. With `JoinPolicy::All` the group waits for
+    /// all of them and fails if any fails. With `First` it is done as soon as
+    /// one succeeds, and the others keep running in the background.
+    fn execute_parallel(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+        variables: &Arc<dyn VariableResolver>,
+    ) -> ActionResult {
+        let Some(group) = action.actions.as_deref().filter(|group| !group.is_empty()) else {
+            log::error!("No actions specified for Parallel action");
+            return ActionResult::new(
+                ActionType::Parallel,
+                false,
+                Some("No actions specified for Parallel action".to_string()),
+            );
+        };
+        let join = action.parameters.join.unwrap_or(JoinPolicy::All);
+
+        let (sender, receiver) = mpsc::channel();
+        for (index, member) in group.iter().enumerate() {
+            let facade = self.clone();
+            let shortcut = shortcut.clone();
+            let member = member.clone();
+            let previous = previous.cloned();
+            let variables = variables.clone();
+            let sender = sender.clone();
+
+            thread::spawn(move || {
+                let result = facade.execute_step(&shortcut, &member, previous.as_ref(), &variables);
+                // The receiver is gone once a First group is done.
+                let _ = sender.send((index, result));
+            });
+        }
+        drop(sender);
+
+        let mut finished: Vec<Option<ActionResult>> = vec![None; group.len()];
+        for (index, result) in receiver {
+            let success = result.success;
+            finished[index] = Some(result);
+            if join == JoinPolicy::First && success {
+                break;
+            }
+        }
+
+        let steps: Vec<ActionResult> = finished.into_iter().flatten().collect();
+        let succeeded = steps.iter().filter(|step| step.success).count();
+        let (success, message) = match join {
+            JoinPolicy::All => (
+                succeeded == group.len(),
+                format!("{} of {} actions succeeded", succeeded, group.len()),
+            ),
+            JoinPolicy::First => match steps.iter().find(|step| step.success) {
+                Some(first) => (true, format!("{:?} finished first", first.action_type)),
+                None => (false, format!("All {} actions failed", group.len())),
+            },
+        };
+
+        let mut result = ActionResult::new(ActionType::Parallel, success, Some(message));
+        result.steps = steps;
+        result
+    }
+
+    fn execute_action(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        previous: Option<&ActionResult>,
+    ) -> Result<ActionOutput, String> {
+        let stdin = match action.parameters.stdin_from_previous {
+            Some(true) => Some(
+                previous
+                    .and_then(|previous| previous.stdout.clone())
+                    .ok_or_else(|| "The previous action has no output to pipe".to_string())?,
+            ),
+            _ => None,
+        };
+
+        match action.action_type {
+            ActionType::OpenFolder | ActionType::OpenFile => {
+                log::info!(
+                    "Open action: {:?} : {:?}",
+                    action.action_type,
+                    action.parameters.path
+                );
+                let Some(path) = &action.parameters.path else {
+                    log::error!("No path specified for {:?} action", action.action_type);
+                    return Err(format!(
+                        "No path specified for {:?} action",
+                        action.action_type
+                    ));
+                };
+
+                for path in resolve_paths(path, action.parameters.base_dir.as_deref())? {
+                    let mut command = Command::new("xdg-open");
+                    command.arg(&path);
+                    self.run_supervised(shortcut, action, &mut command, None)?;
                 }
-                ActionType::RunShellScript => {
-                    if let Some(script) = &action.parameters.script {
-                        Command::new("sh")
-                            .arg("-c")
-                            .arg(script)
-                            .spawn()
-                            .map_err(|e| e.to_string())?;
+            }
+            ActionType::OpenUrl => {
+                let Some(url) = &action.parameters.url else {
+                    log::error!("No url specified for OpenUrl action");
+                    return Err("No url specified for OpenUrl action".to_string());
+                };
+                let url = validate_url(url)?;
+
+                let mut command = open_url_command(
+                    &url,
+                    action.parameters.browser.as_deref(),
+                    action.parameters.browser_profile.as_deref(),
+                )?;
+                self.run_supervised(shortcut, action, &mut command, None)?;
+            }
+            ActionType::OpenApplication => {
+                let Some(app_name) = &action.parameters.app_name else {
+                    log::error!("No app_name specified for OpenApplication action");
+                    return Err("No app_name specified for OpenApplication action".to_string());
+                };
+
+                let args = action.parameters.args.clone().unwrap_or_default();
+                let mut command = application_command(app_name, &args)?;
+                apply_command_options(&mut command, &action.parameters)?;
+                self.run_supervised(shortcut, action, &mut command, stdin)?;
+            }
+            ActionType::ToggleApplication => {
+                let Some(app_name) = &action.parameters.app_name else {
+                    log::error!("No app_name specified for ToggleApplication action");
+                    return Err("No app_name specified for ToggleApplication action".to_string());
+                };
+
+                let window_manager = X11WindowManager::connect()?;
+                let message = match toggle_application(&window_manager, app_name)? {
+                    ToggleOutcome::Focused => format!("Focused {}", app_name),
+                    ToggleOutcome::Hidden => format!("Hid {}", app_name),
+                    ToggleOutcome::NotRunning => {
+                        let args = action.parameters.args.clone().unwrap_or_default();
+                        let mut command = application_command(app_name, &args)?;
+                        apply_command_options(&mut command, &action.parameters)?;
+                        self.run_supervised(shortcut, action, &mut command, stdin)?;
+                        format!("Launched {}", app_name)
                     }
+                };
+                log::info!("{}", message);
+                return Ok(ActionOutput::message(message));
+            }
+            ActionType::SendKeys => {
+                let Some(key) = &action.parameters.key else {
+                    log::error!("No key specified for SendKeys action");
+                    return Err("No key specified for SendKeys action".to_string());
+                };
+                let modifiers = action.parameters.modifiers.clone().unwrap_or_default();
+                let combos = parse_key_sequence(key, &modifiers)?;
+
+                let mut injector = default_key_injector()?;
+                send_key_combos(injector.as_mut(), &combos, action.parameters.key_delay_ms)?;
+            }
+            ActionType::TypeText => {
+                let Some(text) = &action.parameters.text else {
+                    log::error!("No text specified for TypeText action");
+                    return Err("No text specified for TypeText action".to_string());
+                };
+
+                let mut injector = default_key_injector()?;
+                type_text(injector.as_mut(), text, action.parameters.key_delay_ms)?;
+            }
+            ActionType::CopyToClipboard => {
+                let Some(text) = &action.parameters.text else {
+                    log::error!("No text specified for CopyToClipboard action");
+                    return Err("No text specified for CopyToClipboard action".to_string());
+                };
+                write_clipboard(text)?;
+            }
+            ActionType::RunShellScript => {
+                let (mut command, inline_file) = script_command(&action.parameters)?;
+                if let Some(limits) = &shortcut.limits {
+                    apply_script_limits(&mut command, limits)?;
                 }
-                _ => {
-                    log::info!(
-                        "Action type {:?} doesn't require command execution",
+                apply_command_options(&mut command, &action.parameters)?;
+
+                let process = self.spawn_process(shortcut, action, &mut command, true, stdin)?;
+                let run = if action.waits() {
+                    Some(process.wait()?)
+                } else {
+                    // The inline script is removed once the interpreter is done with it.
+                    if let Some(inline_file) = inline_file {
+                        thread::spawn(move || {
+                            let _ = process.wait();
+                            drop(inline_file);
+                        });
+                    }
+                    None
+                };
+                return Ok(ActionOutput {
+                    message: run
+                        .as_ref()
+                        .map(|run| format!("Finished in {}ms", run.duration_ms)),
+                    run,
+                });
+            }
+            ActionType::Parallel
+            | ActionType::RunShortcut
+            | ActionType::Toggle
+            | ActionType::ChoiceMenu => {
+                return Err(format!(
+                    "{:?} actions run through execute_step",
+                    action.action_type
+                ));
+            }
+            ActionType::SetState => {
+                let Some(key) = &action.parameters.state_key else {
+                    log::error!("No state_key specified for SetState action");
+                    return Err("No state_key specified for SetState action".to_string());
+                };
+                let scope = action.parameters.state_scope.unwrap_or_default();
+                let value = action.parameters.state_value.clone();
+                StateStore::global().set(&shortcut.id, scope, key, value.clone())?;
+
+                return Ok(ActionOutput::message(match value {
+                    Some(value) => format!("Set {} to {}", key, value),
+                    None => format!("Removed {}", key),
+                }));
+            }
+            ActionType::Delay => {
+                let Some(delay_ms) = action.parameters.delay_ms else {
+                    log::error!("No delay_ms specified for Delay action");
+                    return Err("No delay_ms specified for Delay action".to_string());
+                };
+                thread::sleep(Duration::from_millis(delay_ms));
+            }
+            ActionType::QuitApplication
+            | ActionType::HideApplication
+            | ActionType::FocusApplication
+            | ActionType::MinimizeWindow
+            | ActionType::MaximizeWindow
+            | ActionType::MoveResizeWindow => {
+                log::info!(
+                    "Window action: {:?} : {:?}",
+                    action.action_type,
+                    action.parameters.app_name
+                );
+                let window_manager = X11WindowManager::connect()?;
+                apply_window_action(&window_manager, &action.action_type, &action.parameters)?;
+            }
+            ActionType::CopyPath | ActionType::MovePath | ActionType::RenamePath => {
+                let (Some(source_path), Some(target_path)) = (
+                    &action.parameters.source_path,
+                    &action.parameters.target_path,
+                ) else {
+                    log::error!(
+                        "{:?} action requires source_path and target_path",
                         action.action_type
                     );
-                }
+                    return Err(format!(
+                        "{:?} action requires source_path and target_path",
+                        action.action_type
+                    ));
+                };
+                let base_dir = action.parameters.base_dir.as_deref();
+                let policy = action.parameters.overwrite.unwrap_or(OverwritePolicy::Skip);
+
+                let message = match action.action_type {
+                    ActionType::RenamePath => {
+                        // A bare name renames within the source directory.
+                        let target = match expand_path(target_path)? {
+                            name if name.components().count() == 1 => name,
+                            _ => resolve_path(target_path, base_dir)?,
+                        };
+                        rename_path(&resolve_path(source_path, base_dir)?, &target, policy)?
+                    }
+                    _ => {
+                        let sources = resolve_paths(source_path, base_dir)?;
+                        let target = resolve_path(target_path, base_dir)?;
+                        if sources.len() > 1 && !target.is_dir() {
+                            return Err(format!(
+                                "{} matches several paths, target {} must be an existing directory",
+                                source_path,
+                                target.display()
+                            ));
+                        }
+
+                        sources
+                            .iter()
+                            .map(|source| match action.action_type {
+                                ActionType::CopyPath => copy_path(source, &target, policy),
+                                _ => move_path(source, &target, policy),
+                            })
+                            .collect::<Result<Vec<_>, _>>()?
+                            .join("\n")
+                    }
+                };
+                log::info!("{}", message);
+                return Ok(ActionOutput::message(message));
             }
         }
-        Ok(())
+        Ok(ActionOutput::default())
+    }
+
+    /// Spawns the command under the process supervisor. When the action waits,
+    /// blocks until the process exits and returns its run, failed or not.
+    fn spawn_supervised(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        command: &mut Command,
+        capture_output: bool,
+        stdin: Option<String>,
+    ) -> Result<Option<ProcessRun>, String> {
+        let process = self.spawn_process(shortcut, action, command, capture_output, stdin)?;
+
+        if !action.waits() {
+            return Ok(None);
+        }
+
+        process.wait().map(Some)
+    }
+
+    /// Starts the action's process under the supervisor without waiting for it.
+    fn spawn_process(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        command: &mut Command,
+        capture_output: bool,
+        stdin: Option<String>,
+    ) -> Result<SupervisedProcess, String> {
+        // The safety limits only apply to scripts.
+        let limits = match action.action_type {
+            ActionType::RunShellScript => shortcut
+                .limits
+                .as_ref()
+                .map(RunLimits::from)
+                .unwrap_or_default(),
+            _ => RunLimits::default(),
+        };
+
+        ProcessSupervisor::global().spawn(
+            &self.app_handle,
+            command,
+            ProcessOrigin {
+                shortcut_id: shortcut.id.clone(),
+                command_name: shortcut.command_name.clone(),
+                action_type: action.action_type.clone(),
+            },
+            capture_output,
+            limits,
+            stdin,
+        )
+    }
+
+    /// Like `spawn_supervised`, but a failed run is an error.
+    fn run_supervised(
+        &self,
+        shortcut: &ExecutionShortcut,
+        action: &ExecutionAction,
+        command: &mut Command,
+        stdin: Option<String>,
+    ) -> Result<(), String> {
+        let run = self.spawn_supervised(shortcut, action, command, false, stdin)?;
+        match run.and_then(|run| run.failure_message()) {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Result of an action that ran a list of steps in its place. The next
+/// condition or `{prev.stdout}` sees the last step that ran.
+fn steps_result(
+    action_type: ActionType,
+    message: String,
+    steps: Vec<ActionResult>,
+) -> ActionResult {
+    let mut result = ActionResult::new(
+        action_type,
+        steps.iter().all(|step| step.success),
+        Some(message),
+    );
+    if let Some(last) = steps.iter().rev().find(|step| !step.skipped) {
+        result.exit_code = last.exit_code;
+        result.stdout = last.stdout.clone();
     }
+    result.steps = steps;
+    result
 }
@@ -0,0 +1,97 @@
+use std::fs;
+use std::sync::Mutex;
+
+use chrono::Local;
+use serde::Serialize;
+
+use super::execution_shortcut::{ActionResult, ExecutionShortcut};
+use super::process_supervisor::logs_dir;
+
+const MAX_HISTORY_ENTRIES: usize = 200;
+const HISTORY_FILE: &str = "history.jsonl";
+
+// Presses of the same shortcut may finish at the same time.
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// One run of a shortcut, appended to `data_dir/logs/<shortcut-id>/history.jsonl`.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub shortcut_id: String,
+    pub command_name: String,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub outcome: ExecutionOutcome,
+    pub confirmation: Option<Confirmation>, // only for shortcuts that require it
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum ExecutionOutcome {
+    Succeeded,
+    Failed,
+    Cancelled, // nothing ran
+}
+
+/// How the user answered the confirmation dialog of a shortcut.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum Confirmation {
+    Confirmed,
+    Declined,
+    TimedOut,
+}
+
+impl HistoryEntry {
+    /// Entry for a run that ended with `outcome`, as `execute_shortcut`
+    /// returns it. `started_at` is when the shortcut was pressed.
+    pub fn new(
+        shortcut: &ExecutionShortcut,
+        started_at: chrono::DateTime<Local>,
+        outcome: &Result<Option<Vec<ActionResult>>, String>,
+        confirmation: Option<Confirmation>,
+    ) -> Self {
+        let (outcome, message) = match outcome {
+            Ok(Some(results)) => match results.iter().find(|result| !result.success) {
+                Some(failed) => (ExecutionOutcome::Failed, failed.message.clone()),
+                None => (ExecutionOutcome::Succeeded, None),
+            },
+            Ok(None) => (ExecutionOutcome::Cancelled, None),
+            Err(error) => (ExecutionOutcome::Failed, Some(error.clone())),
+        };
+
+        Self {
+            shortcut_id: shortcut.id.clone(),
+            command_name: shortcut.command_name.clone(),
+            started_at: started_at.to_rfc3339(),
+            duration_ms: (Local::now() - started_at).num_milliseconds().max(0) as u64,
+            outcome,
+            confirmation,
+            message,
+        }
+    }
+}
+
+/// Appends the entry to the shortcut's history, keeping the latest
+/// `MAX_HISTORY_ENTRIES`.
+pub fn record_execution(entry: &HistoryEntry) -> Result<(), String> {
+    let dir = logs_dir(&entry.shortcut_id)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(HISTORY_FILE);
+
+    let _guard = HISTORY_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines.push(&line);
+
+    let excess = lines.len().saturating_sub(MAX_HISTORY_ENTRIES);
+    let mut content = lines[excess..].join("\n");
+    content.push('\n');
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
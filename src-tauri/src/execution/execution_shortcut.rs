@@ -1,10 +1,13 @@
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::definition::action::{ActionParameters, ActionScope, ActionType};
-use crate::definition::shortcut::Shortcut;
+use crate::definition::action::{
+    ActionCondition, ActionParameters, ActionScope, ActionType, MenuChoice, OnFailure,
+    ScriptLimits, ShortcutAction,
+};
+use crate::definition::shortcut::{ExecutionMode, ParameterPrompt, Shortcut};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionShortcut {
     pub id: String,
     pub key_combination: String,
@@ -12,12 +15,50 @@ pub struct ExecutionShortcut {
     pub enabled: bool,
     pub actions: Vec<ExecutionAction>,
     pub scope: Option<ActionScope>,
+    pub limits: Option<ScriptLimits>,
+    pub execution_mode: Option<ExecutionMode>,
+    pub cycle_reset_after_secs: Option<u64>,
+    pub prompts: Option<Vec<ParameterPrompt>>,
+    pub require_confirmation: Option<bool>,
+    pub confirmation_timeout_secs: Option<u64>,
+    #[serde(skip)]
+    pub depth: usize, // how many RunShortcut actions deep it runs
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionAction {
     pub action_type: ActionType,
     pub parameters: ActionParameters,
+    pub wait: Option<bool>,
+    pub on_failure: Option<OnFailure>,
+    pub actions: Option<Vec<ExecutionAction>>,
+    pub condition: Option<ActionCondition>,
+    pub else_actions: Option<Vec<ExecutionAction>>,
+    pub alternate_actions: Option<Vec<ExecutionAction>>,
+    pub choices: Option<Vec<ExecutionChoice>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionChoice {
+    pub name: String,
+    pub key: Option<String>,
+    pub actions: Vec<ExecutionAction>,
+}
+
+impl ExecutionAction {
+    /// Whether the pipeline waits for the action's process to exit. Launched
+    /// applications, browsers and file handlers run until the user closes them,
+    /// so they aren't waited for unless asked to.
+    pub fn waits(&self) -> bool {
+        self.wait.unwrap_or(!matches!(
+            self.action_type,
+            ActionType::OpenApplication
+                | ActionType::ToggleApplication
+                | ActionType::OpenUrl
+                | ActionType::OpenFile
+                | ActionType::OpenFolder
+        ))
+    }
 }
 
 impl From<Shortcut> for ExecutionShortcut {
@@ -27,15 +68,97 @@ impl From<Shortcut> for ExecutionShortcut {
             key_combination: shortcut.key_combination,
             command_name: shortcut.command_name,
             enabled: shortcut.enabled,
-            actions: shortcut
-                .actions
-                .into_iter()
-                .map(|action| ExecutionAction {
-                    action_type: action.action_type,
-                    parameters: action.parameters,
-                })
-                .collect(),
+            actions: shortcut.actions.into_iter().map(Into::into).collect(),
             scope: shortcut.scope,
+            limits: shortcut.limits,
+            execution_mode: shortcut.execution_mode,
+            cycle_reset_after_secs: shortcut.cycle_reset_after_secs,
+            prompts: shortcut.prompts,
+            require_confirmation: shortcut.require_confirmation,
+            confirmation_timeout_secs: shortcut.confirmation_timeout_secs,
+            depth: 0,
+        }
+    }
+}
+
+impl From<ShortcutAction> for ExecutionAction {
+    fn from(action: ShortcutAction) -> Self {
+        Self {
+            action_type: action.action_type,
+            parameters: action.parameters,
+            wait: action.wait,
+            on_failure: action.on_failure,
+            actions: action
+                .actions
+                .map(|actions| actions.into_iter().map(Into::into).collect()),
+            condition: action.condition,
+            else_actions: action
+                .else_actions
+                .map(|actions| actions.into_iter().map(Into::into).collect()),
+            alternate_actions: action
+                .alternate_actions
+                .map(|actions| actions.into_iter().map(Into::into).collect()),
+            choices: action
+                .choices
+                .map(|choices| choices.into_iter().map(Into::into).collect()),
+        }
+    }
+}
+
+impl From<MenuChoice> for ExecutionChoice {
+    fn from(choice: MenuChoice) -> Self {
+        Self {
+            name: choice.name,
+            key: choice.key,
+            actions: choice.actions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionResult {
+    pub action_type: ActionType,
+    pub success: bool,
+    pub message: Option<String>,
+    pub skipped: bool, // its condition didn't hold and there was no else branch
+    pub attempts: u32,
+    pub duration_ms: u64,
+    pub exit_code: Option<i32>,
+    pub stdout: Option<String>,
+    pub steps: Vec<ActionResult>, // results of a group or an else branch
+}
+
+impl ActionResult {
+    pub fn new(action_type: ActionType, success: bool, message: Option<String>) -> Self {
+        Self {
+            action_type,
+            success,
+            message,
+            skipped: false,
+            attempts: 1,
+            duration_ms: 0,
+            exit_code: None,
+            stdout: None,
+            steps: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShortcutEventPayload {
+    pub shortcut_id: String,
+    pub command_name: String,
+    pub state: String,
+    pub results: Vec<ActionResult>,
+}
+
+impl ShortcutEventPayload {
+    pub fn new(shortcut: &ExecutionShortcut, state: &str, results: Vec<ActionResult>) -> Self {
+        Self {
+            shortcut_id: shortcut.id.clone(),
+            command_name: shortcut.command_name.clone(),
+            state: state.to_string(),
+            results,
         }
     }
 }
@@ -0,0 +1,457 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::definition::action::OverwritePolicy;
+
+pub fn copy_path(source: &Path, target: &Path, policy: OverwritePolicy) -> Result<String, String> {
+    ensure_exists(source)?;
+    let target = place_inside_directory(source, target);
+
+    if source.is_dir() && lies_within(&target, source) {
+        return Err(format!("Cannot copy {} into itself", source.display()));
+    }
+
+    let Some(destination) = destination(source, target.clone(), policy)? else {
+        return Ok(format!("Skipped copy, {} already exists", target.display()));
+    };
+
+    copy_into_place(source, &destination).map_err(|e| {
+        log::error!(
+            "Failed to copy {} to {}: {}",
+            source.display(),
+            destination.display(),
+            e
+        );
+        format!("Failed to copy {}: {}", source.display(), e)
+    })?;
+
+    Ok(format!(
+        "Copied {} to {}",
+        source.display(),
+        destination.display()
+    ))
+}
+
+pub fn move_path(source: &Path, target: &Path, policy: OverwritePolicy) -> Result<String, String> {
+    ensure_exists(source)?;
+    let target = place_inside_directory(source, target);
+
+    if source.is_dir() && lies_within(&target, source) {
+        return Err(format!("Cannot move {} into itself", source.display()));
+    }
+
+    let Some(destination) = destination(source, target.clone(), policy)? else {
+        return Ok(format!("Skipped move, {} already exists", target.display()));
+    };
+
+    move_or_copy(source, &destination)?;
+
+    Ok(format!(
+        "Moved {} to {}",
+        source.display(),
+        destination.display()
+    ))
+}
+
+/// Renames `source` in place when `target` is a bare file name, or moves it to
+/// `target` when it is a path.
+pub fn rename_path(
+    source: &Path,
+    target: &Path,
+    policy: OverwritePolicy,
+) -> Result<String, String> {
+    ensure_exists(source)?;
+
+    let target = match (target.components().count(), source.parent()) {
+        (1, Some(parent)) if target.is_relative() => parent.join(target),
+        _ => target.to_path_buf(),
+    };
+
+    let Some(destination) = destination(source, target.clone(), policy)? else {
+        return Ok(format!(
+            "Skipped rename, {} already exists",
+            target.display()
+        ));
+    };
+
+    move_or_copy(source, &destination)?;
+
+    Ok(format!(
+        "Renamed {} to {}",
+        source.display(),
+        destination.display()
+    ))
+}
+
+fn ensure_exists(source: &Path) -> Result<(), String> {
+    if source.symlink_metadata().is_err() {
+        log::error!("Source path does not exist: {}", source.display());
+        return Err(format!("Source path does not exist: {}", source.display()));
+    }
+    Ok(())
+}
+
+/// Copying or moving onto an existing directory puts the source inside it.
+fn place_inside_directory(source: &Path, target: &Path) -> PathBuf {
+    match source.file_name() {
+        Some(name) if target.is_dir() => target.join(name),
+        _ => target.to_path_buf(),
+    }
+}
+
+/// Whether `path` is `directory` or inside it, once `.`, `..` and symlinked
+/// parents are resolved in both.
+fn lies_within(path: &Path, directory: &Path) -> bool {
+    match (fs::canonicalize(directory), resolve_parents(path)) {
+        (Ok(directory), Some(path)) => path.starts_with(directory),
+        _ => false,
+    }
+}
+
+/// `path` with its existing ancestors canonicalized. The last component is not
+/// followed, a target that is a symlink gets replaced rather than written through.
+fn resolve_parents(path: &Path) -> Option<PathBuf> {
+    let Some(name) = path.file_name() else {
+        return fs::canonicalize(path).ok();
+    };
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    match fs::canonicalize(parent) {
+        Ok(parent) => Some(parent.join(name)),
+        Err(_) => resolve_parents(parent).map(|parent| parent.join(name)),
+    }
+}
+
+/// Applies the overwrite policy. `None` means the operation should be skipped.
+/// A replaced target is left in place until the copy or move has succeeded.
+fn destination(
+    source: &Path,
+    destination: PathBuf,
+    policy: OverwritePolicy,
+) -> Result<Option<PathBuf>, String> {
+    if destination.symlink_metadata().is_err() {
+        return Ok(Some(destination));
+    }
+    if destination == source {
+        return Err(format!(
+            "Source and target are the same: {}",
+            source.display()
+        ));
+    }
+
+    match policy {
+        OverwritePolicy::Skip => {
+            log::info!("Target exists, skipping: {}", destination.display());
+            Ok(None)
+        }
+        OverwritePolicy::Replace => {
+            log::info!("Target exists, replacing: {}", destination.display());
+            Ok(Some(destination))
+        }
+        OverwritePolicy::AutoSuffix => Ok(Some(suffixed_path(&destination))),
+    }
+}
+
+/// First free "name (n).ext" variant of `path`.
+fn suffixed_path(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let (stem, extension) = if path.is_dir() {
+        (path.file_name(), None)
+    } else {
+        (path.file_stem(), path.extension())
+    };
+    let stem = stem
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut n = 1;
+    loop {
+        let name = match extension {
+            Some(extension) => format!("{} ({}).{}", stem, n, extension.to_string_lossy()),
+            None => format!("{} ({})", stem, n),
+        };
+        let candidate = parent.join(name);
+        if candidate.symlink_metadata().is_err() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+fn move_or_copy(source: &Path, destination: &Path) -> Result<(), String> {
+    match replace_path(source, destination) {
+        Ok(()) => Ok(()),
+        // Renaming across filesystems is not possible, fall back to copy + delete.
+        Err(e) if e.raw_os_error() == Some(libc::EXDEV) => copy_into_place(source, destination)
+            .and_then(|_| remove_path(source))
+            .map_err(|e| {
+                log::error!(
+                    "Failed to move {} to {}: {}",
+                    source.display(),
+                    destination.display(),
+                    e
+                );
+                format!("Failed to move {}: {}", source.display(), e)
+            }),
+        Err(e) => {
+            log::error!(
+                "Failed to move {} to {}: {}",
+                source.display(),
+                destination.display(),
+                e
+            );
+            Err(format!("Failed to move {}: {}", source.display(), e))
+        }
+    }
+}
+
+/// Copies `source` to `destination` through a staged copy next to it, which
+/// is only renamed into place once complete. A failed copy leaves neither a
+/// partial tree nor a damaged destination behind.
+fn copy_into_place(source: &Path, destination: &Path) -> io::Result<()> {
+    let staged = sibling_temp_path(destination);
+    let copied = copy_recursive(source, &staged).and_then(|_| replace_path(&staged, destination));
+    if copied.is_err() && staged.symlink_metadata().is_ok() {
+        if let Err(e) = remove_path(&staged) {
+            log::warn!("Failed to remove {}: {}", staged.display(), e);
+        }
+    }
+    copied
+}
+
+/// Renames `source` to `target`, replacing whatever is there. `fs::rename`
+/// replaces files by itself; a directory, or a target of another type, is
+/// moved aside first and put back when the rename fails.
+fn replace_path(source: &Path, target: &Path) -> io::Result<()> {
+    let error = match fs::rename(source, target) {
+        Ok(()) => return Ok(()),
+        Err(e) => e,
+    };
+    if error.raw_os_error() == Some(libc::EXDEV) || target.symlink_metadata().is_err() {
+        return Err(error);
+    }
+
+    let backup = sibling_temp_path(target);
+    fs::rename(target, &backup)?;
+    if let Err(e) = fs::rename(source, target) {
+        if let Err(restore_error) = fs::rename(&backup, target) {
+            log::error!(
+                "Failed to restore {} from {}: {}",
+                target.display(),
+                backup.display(),
+                restore_error
+            );
+        }
+        return Err(e);
+    }
+    if let Err(e) = remove_path(&backup) {
+        log::warn!("Failed to remove replaced {}: {}", backup.display(), e);
+    }
+    Ok(())
+}
+
+/// Unused hidden path next to `path`.
+fn sibling_temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, Uuid::new_v4().simple()))
+}
+
+fn copy_recursive(source: &Path, destination: &Path) -> io::Result<()> {
+    let file_type = source.symlink_metadata()?.file_type();
+
+    if file_type.is_symlink() {
+        let link = fs::read_link(source)?;
+        std::os::unix::fs::symlink(link, destination)
+    } else if file_type.is_dir() {
+        fs::create_dir_all(destination)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        fs::copy(source, destination).map(|_| ())
+    }
+}
+
+fn remove_path(path: &Path) -> io::Result<()> {
+    if path.symlink_metadata()?.file_type().is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::net::UnixListener;
+
+    use super::*;
+
+    /// Directory removed with everything in it when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let path = std::env::temp_dir().join(format!("file-operations-{}", Uuid::new_v4()));
+            fs::create_dir(&path).unwrap();
+            Self(path)
+        }
+
+        fn file(&self, name: &str, contents: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, contents).unwrap();
+            path
+        }
+
+        fn names(&self) -> Vec<String> {
+            let mut names: Vec<String> = fs::read_dir(&self.0)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn read(path: &Path) -> String {
+        fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn copies_into_existing_directories() {
+        let dir = TempDir::new();
+        let source = dir.file("src/a.txt", "a");
+        fs::create_dir(dir.0.join("dst")).unwrap();
+
+        copy_path(
+            &dir.0.join("src"),
+            &dir.0.join("dst"),
+            OverwritePolicy::Skip,
+        )
+        .unwrap();
+        copy_path(&source, &dir.0.join("dst"), OverwritePolicy::Skip).unwrap();
+
+        assert_eq!(read(&dir.0.join("dst/src/a.txt")), "a");
+        assert_eq!(read(&dir.0.join("dst/a.txt")), "a");
+        assert_eq!(read(&source), "a");
+    }
+
+    #[test]
+    fn applies_overwrite_policies() {
+        let dir = TempDir::new();
+        let source = dir.file("a.txt", "new");
+        let target = dir.file("b.txt", "old");
+
+        copy_path(&source, &target, OverwritePolicy::Skip).unwrap();
+        assert_eq!(read(&target), "old");
+
+        copy_path(&source, &target, OverwritePolicy::AutoSuffix).unwrap();
+        copy_path(&source, &target, OverwritePolicy::AutoSuffix).unwrap();
+        assert_eq!(read(&dir.0.join("b (1).txt")), "new");
+        assert_eq!(read(&dir.0.join("b (2).txt")), "new");
+
+        copy_path(&source, &target, OverwritePolicy::Replace).unwrap();
+        assert_eq!(read(&target), "new");
+        assert_eq!(dir.names(), ["a.txt", "b (1).txt", "b (2).txt", "b.txt"]);
+    }
+
+    #[test]
+    fn replaces_directories() {
+        let dir = TempDir::new();
+        dir.file("src/new.txt", "new");
+        let target = dir
+            .file("dst/old.txt", "old")
+            .parent()
+            .unwrap()
+            .to_path_buf();
+
+        rename_path(
+            &dir.0.join("src"),
+            Path::new("dst"),
+            OverwritePolicy::Replace,
+        )
+        .unwrap();
+        assert_eq!(read(&target.join("new.txt")), "new");
+        assert!(!target.join("old.txt").exists());
+        assert_eq!(dir.names(), ["dst"]);
+    }
+
+    #[test]
+    fn refuses_to_copy_a_directory_into_itself() {
+        let dir = TempDir::new();
+        dir.file("src/sub/a.txt", "a");
+        fs::create_dir(dir.0.join("other")).unwrap();
+        let source = dir.0.join("src");
+        std::os::unix::fs::symlink(source.join("sub"), dir.0.join("link")).unwrap();
+
+        for target in [
+            source.join("sub"),
+            source.join("sub/../sub/copy"),
+            dir.0.join("src/./sub/copy"),
+            dir.0.join("other/../src/copy"),
+            dir.0.join("link/copy"),
+        ] {
+            let copied = copy_path(&source, &target, OverwritePolicy::Skip);
+            assert!(copied.unwrap_err().contains("into itself"), "{:?}", target);
+            let moved = move_path(&source, &target, OverwritePolicy::Skip);
+            assert!(moved.unwrap_err().contains("into itself"), "{:?}", target);
+        }
+        assert!(copy_path(&source, &dir.0.join("src-copy"), OverwritePolicy::Skip).is_ok());
+    }
+
+    #[test]
+    fn failed_copies_keep_the_replaced_target() {
+        let dir = TempDir::new();
+        dir.file("src/a.txt", "a");
+        // Sockets can't be copied, even by root.
+        let _socket = UnixListener::bind(dir.0.join("src/socket")).unwrap();
+        let target = dir.file("dst.txt", "old");
+
+        let copied = copy_path(&dir.0.join("src"), &target, OverwritePolicy::Replace);
+        assert!(copied.is_err());
+        assert_eq!(read(&target), "old");
+        assert_eq!(dir.names(), ["dst.txt", "src"]);
+    }
+
+    #[test]
+    fn failed_copies_leave_no_partial_tree() {
+        let dir = TempDir::new();
+        dir.file("src/a.txt", "a");
+        let _socket = UnixListener::bind(dir.0.join("src/socket")).unwrap();
+
+        let copied = copy_path(
+            &dir.0.join("src"),
+            &dir.0.join("dst"),
+            OverwritePolicy::Skip,
+        );
+        assert!(copied.is_err());
+        assert_eq!(dir.names(), ["src"]);
+    }
+
+    #[test]
+    fn renames_in_place() {
+        let dir = TempDir::new();
+        let source = dir.file("a.txt", "a");
+
+        rename_path(&source, Path::new("b.txt"), OverwritePolicy::Skip).unwrap();
+        assert_eq!(dir.names(), ["b.txt"]);
+        assert!(rename_path(&source, Path::new("c.txt"), OverwritePolicy::Skip).is_err());
+    }
+}
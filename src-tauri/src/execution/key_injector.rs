@@ -0,0 +1,404 @@
+use std::thread;
+use std::time::Duration;
+
+use super::clipboard::{read_clipboard, write_clipboard};
+use super::uinput_key_injector::UinputKeyInjector;
+use super::xtest_key_injector::XTestKeyInjector;
+
+const DEFAULT_KEY_DELAY_MS: u64 = 12;
+const CLIPBOARD_RESTORE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    Super,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 4] = [
+        Modifier::Control,
+        Modifier::Shift,
+        Modifier::Alt,
+        Modifier::Super,
+    ];
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_uppercase().as_str() {
+            "CTRL" | "CONTROL" => Some(Modifier::Control),
+            "SHIFT" => Some(Modifier::Shift),
+            "ALT" | "OPTION" => Some(Modifier::Alt),
+            "SUPER" | "CMD" | "META" | "WIN" | "WINDOWS" => Some(Modifier::Super),
+            _ => None,
+        }
+    }
+
+    /// Key name of the left-hand variant of the modifier.
+    pub fn key_name(&self) -> &'static str {
+        match self {
+            Modifier::Control => "Control_L",
+            Modifier::Shift => "Shift_L",
+            Modifier::Alt => "Alt_L",
+            Modifier::Super => "Super_L",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyCombo {
+    pub modifiers: Vec<Modifier>,
+    /// Canonical key name, using X keysym naming ("Return", "a", "F5").
+    pub key: String,
+}
+
+/// Key producing a character in the active keyboard layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CharacterKey {
+    pub key: String,
+    pub shift: bool,
+}
+
+/// Backend able to synthesise key events into the focused window.
+pub trait KeyInjector {
+    /// Keeps modifiers that are still physically held, typically the ones of the
+    /// shortcut that triggered the action, from combining with the sent keys.
+    fn release_held_modifiers(&mut self) -> Result<(), String>;
+    fn key_event(&mut self, key: &str, pressed: bool) -> Result<(), String>;
+    /// `None` when the layout has no key (with or without Shift) for the character.
+    fn character_key(&mut self, character: char) -> Option<CharacterKey>;
+}
+
+/// XTest when an X server is reachable, uinput otherwise (Wayland, TTY).
+pub fn default_key_injector() -> Result<Box<dyn KeyInjector>, String> {
+    match XTestKeyInjector::connect() {
+        Ok(injector) => Ok(Box::new(injector)),
+        Err(e) => {
+            log::info!("XTest unavailable ({}), falling back to uinput", e);
+            Ok(Box::new(UinputKeyInjector::create()?))
+        }
+    }
+}
+
+/// Parses a whitespace separated sequence of combos such as `ctrl+shift+c` or
+/// `Down Down Return`. `extra_modifiers` are held for every combo of the sequence.
+pub fn parse_key_sequence(
+    sequence: &str,
+    extra_modifiers: &[String],
+) -> Result<Vec<KeyCombo>, String> {
+    let extra_modifiers = extra_modifiers
+        .iter()
+        .map(|name| Modifier::parse(name).ok_or_else(|| format!("Unknown modifier: {}", name)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let combos = sequence
+        .split_whitespace()
+        .map(|combo| parse_key_combo(combo, &extra_modifiers))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if combos.is_empty() {
+        return Err("Key sequence is empty".to_string());
+    }
+    Ok(combos)
+}
+
+fn parse_key_combo(combo: &str, extra_modifiers: &[Modifier]) -> Result<KeyCombo, String> {
+    let mut modifiers = extra_modifiers.to_vec();
+    let mut key = None;
+
+    // A trailing "+" is the plus key itself, as in "ctrl++".
+    let parts: Vec<&str> = match combo.strip_suffix("++") {
+        Some(rest) => rest.split('+').chain(std::iter::once("plus")).collect(),
+        None if combo == "+" => vec!["plus"],
+        None => combo.split('+').collect(),
+    };
+
+    for part in parts {
+        match Modifier::parse(part) {
+            Some(modifier) if key.is_none() => {
+                if !modifiers.contains(&modifier) {
+                    modifiers.push(modifier);
+                }
+            }
+            _ if key.is_none() && !part.is_empty() => key = Some(canonical_key_name(part)),
+            _ => return Err(format!("Invalid key combination: {}", combo)),
+        }
+    }
+
+    // A lone modifier ("super") taps the modifier key itself.
+    let key = match key {
+        Some(key) => key,
+        None => modifiers
+            .pop()
+            .map(|modifier| modifier.key_name().to_string())
+            .ok_or_else(|| format!("No key in combination: {}", combo))?,
+    };
+    Ok(KeyCombo { modifiers, key })
+}
+
+/// Maps friendly aliases to X keysym names; anything else is passed through so
+/// every keysym name ("XF86AudioMute", "KP_Enter") stays usable.
+pub fn canonical_key_name(name: &str) -> String {
+    let canonical = match name.to_lowercase().as_str() {
+        "enter" | "return" => "Return",
+        "esc" | "escape" => "Escape",
+        "space" => "space",
+        "tab" => "Tab",
+        "backspace" => "BackSpace",
+        "delete" | "del" => "Delete",
+        "insert" | "ins" => "Insert",
+        "home" => "Home",
+        "end" => "End",
+        "pageup" | "pgup" => "Prior",
+        "pagedown" | "pgdn" => "Next",
+        "up" => "Up",
+        "down" => "Down",
+        "left" => "Left",
+        "right" => "Right",
+        "plus" => "plus",
+        "minus" => "minus",
+        "comma" => "comma",
+        "period" | "dot" => "period",
+        "slash" => "slash",
+        _ if name.chars().count() == 1 => return name.to_lowercase(),
+        _ if is_function_key(name) => return name.to_uppercase(),
+        _ => name,
+    };
+    canonical.to_string()
+}
+
+fn is_function_key(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some('f' | 'F'))
+        && chars
+            .as_str()
+            .parse::<u8>()
+            .is_ok_and(|n| (1..=24).contains(&n))
+}
+
+pub fn send_key_combos(
+    injector: &mut dyn KeyInjector,
+    combos: &[KeyCombo],
+    key_delay_ms: Option<u64>,
+) -> Result<(), String> {
+    let delay = Duration::from_millis(key_delay_ms.unwrap_or(DEFAULT_KEY_DELAY_MS));
+
+    injector.release_held_modifiers()?;
+    thread::sleep(delay);
+
+    for combo in combos {
+        log::debug!("Sending key combo: {:?}", combo);
+        send_combo(injector, combo, delay)?;
+    }
+
+    Ok(())
+}
+
+fn send_combo(
+    injector: &mut dyn KeyInjector,
+    combo: &KeyCombo,
+    delay: Duration,
+) -> Result<(), String> {
+    let mut keys: Vec<&str> = combo.modifiers.iter().map(Modifier::key_name).collect();
+    keys.push(&combo.key);
+    press_and_release(injector, &keys, delay)?;
+    thread::sleep(delay);
+
+    Ok(())
+}
+
+/// Presses `keys` in order, waits `hold` and releases them in reverse order.
+/// Every key that went down is released, even when pressing or releasing
+/// another one fails, so no modifier is left stuck.
+fn press_and_release(
+    injector: &mut dyn KeyInjector,
+    keys: &[&str],
+    hold: Duration,
+) -> Result<(), String> {
+    let mut pressed = 0;
+    let mut result = Ok(());
+
+    for key in keys {
+        match injector.key_event(key, true) {
+            Ok(()) => pressed += 1,
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+    if result.is_ok() {
+        thread::sleep(hold);
+    }
+
+    for key in keys[..pressed].iter().rev() {
+        if let Err(e) = injector.key_event(key, false) {
+            log::error!("Failed to release key {}: {}", key, e);
+            if result.is_ok() {
+                result = Err(e);
+            }
+        }
+    }
+
+    result
+}
+
+/// Types `text` key by key. Runs of characters the layout cannot produce are
+/// pasted through the clipboard instead, restoring its previous content afterwards.
+pub fn type_text(
+    injector: &mut dyn KeyInjector,
+    text: &str,
+    key_delay_ms: Option<u64>,
+) -> Result<(), String> {
+    let delay = Duration::from_millis(key_delay_ms.unwrap_or(DEFAULT_KEY_DELAY_MS));
+    let mut unsupported = String::new();
+
+    injector.release_held_modifiers()?;
+    thread::sleep(delay);
+
+    for character in text.chars() {
+        let key = match character {
+            '\n' => Some(CharacterKey {
+                key: "Return".to_string(),
+                shift: false,
+            }),
+            '\t' => Some(CharacterKey {
+                key: "Tab".to_string(),
+                shift: false,
+            }),
+            '\r' => continue,
+            _ => injector.character_key(character),
+        };
+
+        let Some(key) = key else {
+            unsupported.push(character);
+            continue;
+        };
+
+        if !unsupported.is_empty() {
+            paste_text(injector, &unsupported, delay)?;
+            unsupported.clear();
+        }
+
+        let keys = if key.shift {
+            vec![Modifier::Shift.key_name(), key.key.as_str()]
+        } else {
+            vec![key.key.as_str()]
+        };
+        press_and_release(injector, &keys, Duration::ZERO)?;
+        thread::sleep(delay);
+    }
+
+    if !unsupported.is_empty() {
+        paste_text(injector, &unsupported, delay)?;
+    }
+
+    Ok(())
+}
+
+fn paste_text(injector: &mut dyn KeyInjector, text: &str, delay: Duration) -> Result<(), String> {
+    log::debug!(
+        "Pasting {} characters through the clipboard",
+        text.chars().count()
+    );
+
+    // Writing back a failed read would wipe what the clipboard holds, like an image.
+    let previous = match read_clipboard() {
+        Ok(previous) => Some(previous),
+        Err(e) => {
+            log::warn!("Not restoring the clipboard after pasting: {}", e);
+            None
+        }
+    };
+    write_clipboard(text)?;
+
+    let paste = KeyCombo {
+        modifiers: vec![Modifier::Control],
+        key: "v".to_string(),
+    };
+    let pasted = send_combo(injector, &paste, delay);
+
+    // Give the target application time to request the selection before restoring it.
+    // A failed combo may still have pasted, and the clipboard is restored either way.
+    thread::sleep(CLIPBOARD_RESTORE_DELAY);
+    let restored = previous.map_or(Ok(()), |previous| write_clipboard(&previous));
+
+    pasted.and(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo(modifiers: &[Modifier], key: &str) -> KeyCombo {
+        KeyCombo {
+            modifiers: modifiers.to_vec(),
+            key: key.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_combos_and_sequences() {
+        assert_eq!(
+            parse_key_sequence("ctrl+shift+C  Down\tenter", &[]),
+            Ok(vec![
+                combo(&[Modifier::Control, Modifier::Shift], "c"),
+                combo(&[], "Down"),
+                combo(&[], "Return"),
+            ])
+        );
+    }
+
+    #[test]
+    fn holds_extra_modifiers_for_every_combo() {
+        let extra = ["Super".to_string(), "ctrl".to_string()];
+
+        assert_eq!(
+            parse_key_sequence("ctrl+a f5", &extra),
+            Ok(vec![
+                combo(&[Modifier::Super, Modifier::Control], "a"),
+                combo(&[Modifier::Super, Modifier::Control], "F5"),
+            ])
+        );
+        assert!(parse_key_sequence("a", &["hyper".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parses_the_plus_key() {
+        assert_eq!(
+            parse_key_sequence("ctrl++ +", &[]),
+            Ok(vec![
+                combo(&[Modifier::Control], "plus"),
+                combo(&[], "plus")
+            ])
+        );
+    }
+
+    #[test]
+    fn taps_lone_modifiers() {
+        assert_eq!(
+            parse_key_sequence("super ctrl+alt", &[]),
+            Ok(vec![
+                combo(&[], "Super_L"),
+                combo(&[Modifier::Control], "Alt_L"),
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_sequences() {
+        for sequence in ["", "  ", "a+b", "ctrl+a+shift", "ctrl+", "ctrl++a"] {
+            assert!(parse_key_sequence(sequence, &[]).is_err(), "{:?}", sequence);
+        }
+    }
+
+    #[test]
+    fn canonicalizes_key_names() {
+        assert_eq!(canonical_key_name("ESC"), "Escape");
+        assert_eq!(canonical_key_name("PgDn"), "Next");
+        assert_eq!(canonical_key_name("A"), "a");
+        assert_eq!(canonical_key_name("f12"), "F12");
+        assert_eq!(canonical_key_name("f25"), "f25");
+        assert_eq!(canonical_key_name("XF86AudioMute"), "XF86AudioMute");
+    }
+}
@@ -1,6 +1,31 @@
+mod clipboard;
+mod command_options;
+mod condition;
+pub mod commands;
+mod desktop_entry;
 mod execution_facade;
+mod execution_history;
 mod execution_shortcut;
+mod file_operations;
+mod key_injector;
+mod path_resolver;
 mod plugins;
+mod popup;
+mod process_lookup;
+mod process_supervisor;
+mod script_command;
+mod script_limits;
+mod state_store;
+mod template;
+mod uinput_key_injector;
+mod url_opener;
+mod window_geometry;
+mod window_manager;
+mod x11_errors;
+mod x11_window_manager;
+mod xtest_key_injector;
 
 pub use execution_facade::ExecutionFacade;
+pub use path_resolver::missing_action_paths;
+pub use state_store::StateStore;
 pub use plugins::setup_global_shortcut_plugin;
@@ -0,0 +1,242 @@
+use std::ffi::{CStr, CString};
+use std::path::{Path, PathBuf};
+
+use crate::definition::action::{ActionParameters, ActionType};
+
+/// Expands `~`, `~user` and `$VAR` / `${VAR}` references in a user supplied path.
+/// Relative paths stay relative.
+pub fn expand_path(path: &str) -> Result<PathBuf, String> {
+    let path = expand_env_vars(path)?;
+
+    let Some(rest) = path.strip_prefix('~') else {
+        return Ok(PathBuf::from(path));
+    };
+    let (user, rest) = rest.split_once('/').unwrap_or((rest, ""));
+    let home = if user.is_empty() {
+        home_dir()?
+    } else {
+        user_home_dir(user)?
+    };
+
+    Ok(if rest.is_empty() {
+        home
+    } else {
+        home.join(rest)
+    })
+}
+
+/// Expands the path and makes it absolute. Relative paths are taken relative to
+/// `base_dir` (itself expanded), or to the home directory when no base is given,
+/// since the working directory of the GUI process is meaningless to the user.
+pub fn resolve_path(path: &str, base_dir: Option<&str>) -> Result<PathBuf, String> {
+    let expanded = expand_path(path)?;
+    if expanded.is_absolute() {
+        return Ok(expanded);
+    }
+
+    let base = match base_dir.filter(|base| !base.is_empty()) {
+        Some(base) => {
+            let base = expand_path(base)?;
+            if !base.is_absolute() {
+                return Err(format!(
+                    "Base directory must be absolute: {}",
+                    base.display()
+                ));
+            }
+            base
+        }
+        None => home_dir()?,
+    };
+
+    Ok(base.join(expanded))
+}
+
+/// Like `resolve_path`, but a path containing glob characters (`*`, `?`, `[`)
+/// resolves to every existing match, in sorted order.
+pub fn resolve_paths(path: &str, base_dir: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    let resolved = resolve_path(path, base_dir)?;
+    if !is_glob(&resolved) || resolved.exists() {
+        return Ok(vec![resolved]);
+    }
+
+    let pattern = resolved.to_string_lossy();
+    let mut matches = glob::glob(&pattern)
+        .map_err(|e| format!("Invalid glob pattern {}: {}", pattern, e))?
+        .filter_map(Result::ok)
+        .collect::<Vec<_>>();
+    matches.sort();
+
+    if matches.is_empty() {
+        log::error!("No paths match {}", pattern);
+        return Err(format!("No paths match {}", pattern));
+    }
+
+    Ok(matches)
+}
+
+/// Warnings for the paths an action reads from that don't exist right now.
+/// Paths with template variables can only be resolved when the shortcut runs
+/// and are not checked.
+pub fn missing_action_paths(
+    action_type: &ActionType,
+    parameters: &ActionParameters,
+) -> Vec<String> {
+    let path = match action_type {
+        ActionType::OpenFolder | ActionType::OpenFile => &parameters.path,
+        ActionType::CopyPath | ActionType::MovePath | ActionType::RenamePath => {
+            &parameters.source_path
+        }
+        _ => return Vec::new(),
+    };
+    let Some(path) = path.as_deref().filter(|path| !path.contains('{')) else {
+        return Vec::new();
+    };
+
+    match resolve_paths(path, parameters.base_dir.as_deref()) {
+        Ok(paths) => paths
+            .into_iter()
+            .filter(|path| !path.exists())
+            .map(|path| format!("{:?}: {} does not exist", action_type, path.display()))
+            .collect(),
+        Err(e) => vec![format!("{:?}: {}", action_type, e)],
+    }
+}
+
+fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+fn home_dir() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+/// Home directory of another user, from the passwd database.
+fn user_home_dir(user: &str) -> Result<PathBuf, String> {
+    let name = CString::new(user).map_err(|e| e.to_string())?;
+    let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let mut buffer = vec![0 as libc::c_char; 16 * 1024];
+
+    let status = unsafe {
+        libc::getpwnam_r(
+            name.as_ptr(),
+            &mut passwd,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            &mut result,
+        )
+    };
+
+    if status != 0 || result.is_null() || passwd.pw_dir.is_null() {
+        log::error!("Unknown user in path: ~{}", user);
+        return Err(format!("Unknown user in path: ~{}", user));
+    }
+
+    let home = unsafe { CStr::from_ptr(passwd.pw_dir) };
+    Ok(PathBuf::from(home.to_string_lossy().to_string()))
+}
+
+/// `${VAR}` must be set. A bare `$VAR` that isn't set is kept as it is, since
+/// real file names may contain a `$`.
+fn expand_env_vars(path: &str) -> Result<String, String> {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(position) = rest.find('$') {
+        expanded.push_str(&rest[..position]);
+        let reference = &rest[position + 1..];
+
+        if let Some(braced) = reference.strip_prefix('{') {
+            match braced.split_once('}').filter(|(name, _)| !name.is_empty()) {
+                Some((name, after)) => {
+                    let value = std::env::var(name)
+                        .map_err(|_| format!("Environment variable {} is not set", name))?;
+                    expanded.push_str(&value);
+                    rest = after;
+                }
+                // An unclosed "${" is kept as is.
+                None => {
+                    expanded.push_str("${");
+                    rest = braced;
+                }
+            }
+            continue;
+        }
+
+        let end = reference
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(reference.len());
+        let name = &reference[..end];
+        match std::env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => expanded.push_str(&value),
+            None => {
+                expanded.push('$');
+                expanded.push_str(name);
+            }
+        }
+        rest = &reference[end..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn home() -> PathBuf {
+        dirs::home_dir().unwrap()
+    }
+
+    #[test]
+    fn expands_the_home_directory() {
+        assert_eq!(expand_path("~"), Ok(home()));
+        assert_eq!(expand_path("~/notes/a.md"), Ok(home().join("notes/a.md")));
+        assert_eq!(expand_path("/tmp/~a"), Ok(PathBuf::from("/tmp/~a")));
+        assert_eq!(expand_path("~root"), user_home_dir("root"));
+        assert!(expand_path("~no-such-user-here/a").is_err());
+    }
+
+    #[test]
+    fn expands_environment_variables() {
+        std::env::set_var("PATH_RESOLVER_TEST_DIR", "/srv/data");
+
+        assert_eq!(
+            expand_path("$PATH_RESOLVER_TEST_DIR/a-$PATH_RESOLVER_TEST_DIR"),
+            Ok(PathBuf::from("/srv/data/a-/srv/data"))
+        );
+        assert_eq!(
+            expand_path("${PATH_RESOLVER_TEST_DIR}_old/a"),
+            Ok(PathBuf::from("/srv/data_old/a"))
+        );
+    }
+
+    #[test]
+    fn keeps_unset_bare_variables() {
+        assert_eq!(
+            expand_path("/tmp/$PATH_RESOLVER_UNSET/price$ 5$/${x"),
+            Ok(PathBuf::from("/tmp/$PATH_RESOLVER_UNSET/price$ 5$/${x"))
+        );
+        assert_eq!(expand_path("a/${}"), Ok(PathBuf::from("a/${}")));
+        assert_eq!(
+            expand_path("${PATH_RESOLVER_UNSET}/a"),
+            Err("Environment variable PATH_RESOLVER_UNSET is not set".to_string())
+        );
+    }
+
+    #[test]
+    fn resolves_relative_paths() {
+        assert_eq!(resolve_path("notes", None), Ok(home().join("notes")));
+        assert_eq!(
+            resolve_path("notes/a.md", Some("/srv")),
+            Ok(PathBuf::from("/srv/notes/a.md"))
+        );
+        assert_eq!(
+            resolve_path("/etc", Some("/srv")),
+            Ok(PathBuf::from("/etc"))
+        );
+        assert_eq!(expand_path("notes"), Ok(PathBuf::from("notes")));
+        assert!(resolve_path("notes", Some("relative")).is_err());
+    }
+}
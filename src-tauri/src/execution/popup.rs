@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use once_cell::sync::OnceCell;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Runtime, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use uuid::Uuid;
+
+use crate::definition::action::ActionType;
+use crate::definition::shortcut::ParameterPrompt;
+
+/// Labels of popup windows start with it, see `capabilities/default.json`.
+const POPUP_LABEL_PREFIX: &str = "popup-";
+
+static POPUPS: OnceCell<Popups> = OnceCell::new();
+
+/// Small windows that ask the user something while a shortcut runs. The
+/// execution thread waits for the answer, while the window's page reads the
+/// request and answers through the `get_popup_request` and `answer_popup`
+/// commands.
+pub struct Popups {
+    pending: Mutex<HashMap<String, PendingPopup>>, // by window label
+}
+
+struct PendingPopup {
+    request: Value,
+    answer: Sender<Option<Value>>,
+}
+
+/// The window a popup opens. `page` is the frontend route it shows.
+pub struct PopupWindow<'a> {
+    pub page: &'a str,
+    pub title: &'a str,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Asks for the values of a shortcut's prompts, answered with a map of
+/// prompt names to values.
+#[derive(Debug, Serialize)]
+pub struct PromptRequest<'a> {
+    pub shortcut_name: &'a str,
+    pub prompts: &'a [ParameterPrompt],
+}
+
+/// Asks which entry of a `ChoiceMenu` to run, answered with its index.
+#[derive(Debug, Serialize)]
+pub struct ChoiceRequest<'a> {
+    pub title: &'a str,
+    pub choices: Vec<ChoiceOption<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChoiceOption<'a> {
+    pub name: &'a str,
+    pub key: Option<&'a str>,
+}
+
+/// Asks to confirm running a shortcut, answered with whether to run it.
+#[derive(Debug, Serialize)]
+pub struct ConfirmRequest<'a> {
+    pub shortcut_name: &'a str,
+    pub key_combination: &'a str,
+    pub actions: Vec<&'a ActionType>,
+    pub timeout_secs: u64,
+}
+
+impl Popups {
+    pub fn global() -> &'static Popups {
+        POPUPS.get_or_init(|| Popups {
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Shows `request` in a popup window and waits for the answer. `None`
+    /// when the user dismissed the popup, or didn't answer within `timeout`.
+    pub fn ask<R: Runtime, A: DeserializeOwned>(
+        &'static self,
+        app_handle: &AppHandle<R>,
+        window: PopupWindow,
+        request: &impl Serialize,
+        timeout: Option<Duration>,
+    ) -> Result<Option<A>, String> {
+        let label = format!("{}{}", POPUP_LABEL_PREFIX, Uuid::new_v4());
+        let request = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        let (sender, receiver) = mpsc::channel();
+        self.lock_pending().insert(
+            label.clone(),
+            PendingPopup {
+                request,
+                answer: sender,
+            },
+        );
+
+        let popup =
+            WebviewWindowBuilder::new(app_handle, &label, WebviewUrl::App(window.page.into()))
+                .title(window.title)
+                .inner_size(window.width, window.height)
+                .resizable(false)
+                .always_on_top(true)
+                .center()
+                .focused(true)
+                .build();
+        let popup = match popup {
+            Ok(popup) => popup,
+            Err(e) => {
+                self.lock_pending().remove(&label);
+                log::error!("Failed to open popup window: {}", e);
+                return Err(format!("Failed to open popup window: {}", e));
+            }
+        };
+
+        // Closing the window dismisses the popup.
+        let closed_label = label.clone();
+        popup.on_window_event(move |event| {
+            if let WindowEvent::Destroyed = event {
+                Popups::global().answer(&closed_label, None);
+            }
+        });
+
+        let answer = match timeout {
+            Some(timeout) => match receiver.recv_timeout(timeout) {
+                Ok(answer) => answer,
+                Err(RecvTimeoutError::Timeout) => {
+                    log::info!("Popup {} timed out after {:?}", label, timeout);
+                    self.lock_pending().remove(&label);
+                    None
+                }
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+            None => receiver.recv().unwrap_or(None),
+        };
+
+        if let Err(e) = popup.close() {
+            log::debug!("Popup {} already closed: {}", label, e);
+        }
+
+        answer
+            .map(|answer| {
+                serde_json::from_value(answer).map_err(|e| format!("Invalid popup answer: {}", e))
+            })
+            .transpose()
+    }
+
+    /// What the popup window `label` asks.
+    pub fn request(&self, label: &str) -> Result<Value, String> {
+        self.lock_pending()
+            .get(label)
+            .map(|pending| pending.request.clone())
+            .ok_or_else(|| format!("Popup {} is not waiting for an answer", label))
+    }
+
+    /// Hands the answer of the popup window `label` to the waiting shortcut,
+    /// `None` dismisses it. Later answers are ignored.
+    pub fn answer(&self, label: &str, answer: Option<Value>) {
+        if let Some(pending) = self.lock_pending().remove(label) {
+            // The shortcut may have stopped waiting in the meantime.
+            let _ = pending.answer.send(answer);
+        }
+    }
+
+    fn lock_pending(&self) -> MutexGuard<'_, HashMap<String, PendingPopup>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
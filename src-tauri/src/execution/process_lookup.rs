@@ -0,0 +1,35 @@
+use std::fs;
+use std::path::Path;
+
+/// Ids of the running processes whose executable or command name matches
+/// `name`, compared case-insensitively.
+pub fn find_processes(name: &str) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir("/proc") else {
+        log::error!("Failed to read /proc");
+        return Vec::new();
+    };
+    let own_pid = std::process::id();
+
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| *pid != own_pid && process_matches(*pid, name))
+        .collect()
+}
+
+fn process_matches(pid: u32, name: &str) -> bool {
+    let process_dir = Path::new("/proc").join(pid.to_string());
+
+    let exe_matches = fs::read_link(process_dir.join("exe"))
+        .ok()
+        .and_then(|exe| {
+            exe.file_name()
+                .map(|file| file.to_string_lossy().eq_ignore_ascii_case(name))
+        })
+        .unwrap_or(false);
+
+    exe_matches
+        || fs::read_to_string(process_dir.join("comm"))
+            .map(|comm| comm.trim().eq_ignore_ascii_case(name))
+            .unwrap_or(false)
+}
@@ -0,0 +1,593 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Local;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::config::AppConfig;
+use crate::definition::action::{ActionType, ScriptLimits};
+
+const MAX_CAPTURED_OUTPUT: usize = 64 * 1024;
+const MAX_RUNS_PER_SHORTCUT: usize = 50;
+const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(3);
+// How long the output may stay open after the process exited.
+const OUTPUT_DRAIN_PERIOD: Duration = Duration::from_millis(500);
+
+static SUPERVISOR: OnceCell<ProcessSupervisor> = OnceCell::new();
+
+/// Outcome of a supervised process, stored in `data_dir/logs/<shortcut-id>/`
+/// and sent to the webview as an `action-completed` event.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessRun {
+    pub shortcut_id: String,
+    pub command_name: String,
+    pub action_type: ActionType,
+    pub pid: u32,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub success: bool,
+    pub cancelled: bool,
+    pub limit_exceeded: Option<ExceededLimit>,
+    pub stdout: String,
+    pub stderr: String,
+    pub stdout_truncated: bool,
+    pub stderr_truncated: bool,
+}
+
+impl ProcessRun {
+    /// Why the run failed, with the last line of its stderr. `None` on success.
+    pub fn failure_message(&self) -> Option<String> {
+        if self.success {
+            return None;
+        }
+
+        let reason = if self.cancelled {
+            "was cancelled".to_string()
+        } else if let Some(limit) = self.limit_exceeded {
+            format!("exceeded its {:?} limit", limit)
+        } else if let Some(code) = self.exit_code {
+            format!("exited with code {}", code)
+        } else if let Some(signal) = self.signal {
+            format!("was killed by signal {}", signal)
+        } else {
+            "failed".to_string()
+        };
+
+        Some(match self.stderr.trim().lines().last() {
+            Some(line) => format!("Process {} {}: {}", self.pid, reason, line),
+            None => format!("Process {} {}", self.pid, reason),
+        })
+    }
+}
+
+/// A spawned process, whose run can be waited for.
+pub struct SupervisedProcess {
+    pub pid: u32,
+    completed: Receiver<ProcessRun>,
+}
+
+impl SupervisedProcess {
+    pub fn wait(self) -> Result<ProcessRun, String> {
+        self.completed
+            .recv()
+            .map_err(|_| format!("Lost track of process {}", self.pid))
+    }
+}
+
+/// What a supervised process was started for.
+#[derive(Debug, Clone)]
+pub struct ProcessOrigin {
+    pub shortcut_id: String,
+    pub command_name: String,
+    pub action_type: ActionType,
+}
+
+/// The limit a process was killed for.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ExceededLimit {
+    Timeout,
+    Output,
+    Cpu,
+}
+
+/// Limits the supervisor enforces while the process runs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunLimits {
+    pub timeout: Option<Duration>,
+    pub max_output_bytes: Option<u64>,
+    pub cpu_limited: bool,
+}
+
+impl From<&ScriptLimits> for RunLimits {
+    fn from(limits: &ScriptLimits) -> Self {
+        Self {
+            timeout: limits.timeout_secs.map(Duration::from_secs),
+            max_output_bytes: limits.max_output_bytes,
+            cpu_limited: limits.cpu_secs.is_some(),
+        }
+    }
+}
+
+/// A supervised process that hasn't exited yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunningAction {
+    pub shortcut_id: String,
+    pub command_name: String,
+    pub action_type: ActionType,
+    pub pid: u32,
+    pub program: String,
+    pub started_at: String,
+    #[serde(skip)]
+    cancelled: bool,
+    #[serde(skip)]
+    limit_exceeded: Option<ExceededLimit>,
+}
+
+/// Output of a process, read on background threads.
+struct OutputCapture {
+    stdout: Option<Arc<Mutex<CapturedStream>>>,
+    stderr: Option<Arc<Mutex<CapturedStream>>>,
+    drained: Receiver<()>, // one message per stream that reached its end
+}
+
+#[derive(Default)]
+struct CapturedStream {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+impl OutputCapture {
+    /// Waits for the streams to reach their end, at most `OUTPUT_DRAIN_PERIOD`
+    /// as the process has already exited. A background job it started (`cmd &`,
+    /// a daemon) inherits the pipes and keeps them open while it runs; it is
+    /// left running and what it writes later isn't captured.
+    fn finish(self, pid: u32) -> ((String, bool), (String, bool)) {
+        let streams = self.stdout.iter().count() + self.stderr.iter().count();
+        let deadline = Instant::now() + OUTPUT_DRAIN_PERIOD;
+
+        for _ in 0..streams {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if self.drained.recv_timeout(left).is_err() {
+                log::warn!(
+                    "Output of process {} is still open after it exited, not waiting for its background jobs",
+                    pid
+                );
+                break;
+            }
+        }
+
+        (captured_text(self.stdout), captured_text(self.stderr))
+    }
+}
+
+fn captured_text(stream: Option<Arc<Mutex<CapturedStream>>>) -> (String, bool) {
+    stream
+        .map(|stream| {
+            let stream = stream
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (
+                String::from_utf8_lossy(&stream.bytes).to_string(),
+                stream.truncated,
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Keeps track of every process the actions spawn. Each one is waited for on
+/// its own thread, so finished children are reaped instead of lingering as
+/// zombies, and can be listed or cancelled while they run.
+pub struct ProcessSupervisor {
+    running: Mutex<HashMap<u32, RunningAction>>,
+}
+
+impl ProcessSupervisor {
+    pub fn global() -> &'static ProcessSupervisor {
+        SUPERVISOR.get_or_init(|| ProcessSupervisor {
+            running: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Spawns `command` in its own process group and supervises it. `stdin`
+    /// is written to the process, which otherwise gets no input. With
+    /// `capture_output` stdout and stderr are captured for the run record,
+    /// otherwise they are discarded. A process that outlives the timeout or
+    /// writes more than the allowed output is killed.
+    pub fn spawn<R: Runtime>(
+        &'static self,
+        app_handle: &AppHandle<R>,
+        command: &mut Command,
+        origin: ProcessOrigin,
+        capture_output: bool,
+        limits: RunLimits,
+        stdin: Option<String>,
+    ) -> Result<SupervisedProcess, String> {
+        let output = || {
+            if capture_output {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            }
+        };
+        let program = command.get_program().to_string_lossy().to_string();
+
+        let mut child = command
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(output())
+            .stderr(output())
+            .process_group(0)
+            .spawn()
+            .map_err(|e| {
+                log::error!("Failed to spawn {}: {}", program, e);
+                format!("Failed to spawn {}: {}", program, e)
+            })?;
+
+        let started = Instant::now();
+        let started_at = Local::now();
+        let pid = child.id();
+
+        self.lock_running().insert(
+            pid,
+            RunningAction {
+                shortcut_id: origin.shortcut_id.clone(),
+                command_name: origin.command_name.clone(),
+                action_type: origin.action_type.clone(),
+                pid,
+                program,
+                started_at: started_at.to_rfc3339(),
+                cancelled: false,
+                limit_exceeded: None,
+            },
+        );
+
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            thread::spawn(move || {
+                // The process may exit without reading all of it.
+                if let Err(e) = pipe.write_all(input.as_bytes()) {
+                    log::debug!("Stopped writing to stdin of process {}: {}", pid, e);
+                }
+            });
+        }
+
+        let output = self.capture_output(&mut child, pid, limits);
+
+        if let Some(timeout) = limits.timeout {
+            thread::spawn(move || {
+                thread::sleep(timeout);
+                self.kill_for_limit(pid, ExceededLimit::Timeout);
+            });
+        }
+
+        let app_handle = app_handle.clone();
+        let (completed_sender, completed) = mpsc::channel();
+        thread::spawn(move || {
+            let status = child.wait();
+            let (cancelled, limit_exceeded) = self
+                .lock_running()
+                .remove(&pid)
+                .map(|action| (action.cancelled, action.limit_exceeded))
+                .unwrap_or_default();
+
+            let duration_ms = started.elapsed().as_millis() as u64;
+            let ((stdout, stdout_truncated), (stderr, stderr_truncated)) = output.finish(pid);
+
+            let (exit_code, signal, stderr) = match status {
+                Ok(status) => (status.code(), status.signal(), stderr),
+                Err(e) => {
+                    log::error!("Failed to wait for process {}: {}", pid, e);
+                    (
+                        None,
+                        None,
+                        format!("{}Failed to wait for process: {}", stderr, e),
+                    )
+                }
+            };
+
+            let limit_exceeded = limit_exceeded.or_else(|| {
+                let cpu_killed = matches!(signal, Some(libc::SIGXCPU | libc::SIGKILL));
+                (limits.cpu_limited && cpu_killed && !cancelled).then_some(ExceededLimit::Cpu)
+            });
+
+            let run = ProcessRun {
+                shortcut_id: origin.shortcut_id,
+                command_name: origin.command_name,
+                action_type: origin.action_type,
+                pid,
+                started_at: started_at.to_rfc3339(),
+                duration_ms,
+                exit_code,
+                signal,
+                success: exit_code == Some(0),
+                cancelled,
+                limit_exceeded,
+                stdout,
+                stderr,
+                stdout_truncated,
+                stderr_truncated,
+            };
+
+            if run.success {
+                log::info!("Process {} finished in {}ms", pid, duration_ms);
+            } else if let Some(limit) = run.limit_exceeded {
+                log::error!("Process {} killed, {:?} limit exceeded", pid, limit);
+            } else {
+                log::error!(
+                    "Process {} failed (exit code {:?}, signal {:?})",
+                    pid,
+                    run.exit_code,
+                    run.signal
+                );
+            }
+
+            let file_name = format!("{}-{}.json", started_at.format("%Y%m%d-%H%M%S%.3f"), pid);
+            if let Err(e) = store_run(&run, &file_name) {
+                log::error!("Failed to store run of process {}: {}", pid, e);
+            }
+            if let Err(e) = app_handle.emit("action-completed", &run) {
+                log::error!("Failed to emit action-completed event: {}", e);
+            }
+            // Nobody may be waiting for it.
+            let _ = completed_sender.send(run);
+        });
+
+        Ok(SupervisedProcess { pid, completed })
+    }
+
+    pub fn running_actions(&self) -> Vec<RunningAction> {
+        let mut actions: Vec<RunningAction> = self.lock_running().values().cloned().collect();
+        actions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        actions
+    }
+
+    /// Sends SIGTERM to the process groups of the shortcut's running actions
+    /// (only the one with `pid` when given), and SIGKILL to those still running
+    /// after the grace period. Returns the number of processes signalled.
+    pub fn cancel(&'static self, shortcut_id: &str, pid: Option<u32>) -> Result<usize, String> {
+        let pids: Vec<u32> = self
+            .lock_running()
+            .values_mut()
+            .filter(|action| action.shortcut_id == shortcut_id)
+            .filter(|action| pid.map_or(true, |pid| action.pid == pid))
+            .map(|action| {
+                action.cancelled = true;
+                action.pid
+            })
+            .collect();
+
+        if pids.is_empty() {
+            return Err(format!("No running actions for shortcut {}", shortcut_id));
+        }
+
+        let signalled = pids.len();
+        self.terminate(pids);
+        Ok(signalled)
+    }
+
+    /// Records the exceeded limit and terminates the process, unless it has
+    /// exited or is already being stopped.
+    fn kill_for_limit(&'static self, pid: u32, limit: ExceededLimit) {
+        {
+            let mut running = self.lock_running();
+            let Some(action) = running.get_mut(&pid) else {
+                return;
+            };
+            if action.cancelled || action.limit_exceeded.is_some() {
+                return;
+            }
+            action.limit_exceeded = Some(limit);
+        }
+
+        log::warn!("Process {} exceeded its {:?} limit", pid, limit);
+        self.terminate(vec![pid]);
+    }
+
+    /// SIGTERM now, SIGKILL for whatever is still running after the grace period.
+    fn terminate(&'static self, pids: Vec<u32>) {
+        for pid in &pids {
+            log::info!("Terminating process group {}", pid);
+            self.signal(*pid, libc::SIGTERM);
+        }
+
+        thread::spawn(move || {
+            thread::sleep(CANCEL_GRACE_PERIOD);
+            for pid in pids {
+                if self.signal(pid, libc::SIGKILL) {
+                    log::warn!("Process {} ignored SIGTERM, killed it", pid);
+                }
+            }
+        });
+    }
+
+    /// Starts capturing the piped stdout and stderr of the child.
+    fn capture_output(
+        &'static self,
+        child: &mut Child,
+        pid: u32,
+        limits: RunLimits,
+    ) -> OutputCapture {
+        let written = Arc::new(AtomicU64::new(0));
+        let (drained_sender, drained) = mpsc::channel();
+
+        OutputCapture {
+            stdout: child.stdout.take().map(|stream| {
+                self.capture(stream, pid, limits, written.clone(), drained_sender.clone())
+            }),
+            stderr: child.stderr.take().map(|stream| {
+                self.capture(stream, pid, limits, written.clone(), drained_sender.clone())
+            }),
+            drained,
+        }
+    }
+
+    /// Reads the stream to the end, keeping only the first `MAX_CAPTURED_OUTPUT`
+    /// bytes. The rest is drained so the process never blocks on a full pipe,
+    /// unless the streams together exceed the output limit and it gets killed.
+    fn capture<S: Read + Send + 'static>(
+        &'static self,
+        mut stream: S,
+        pid: u32,
+        limits: RunLimits,
+        written: Arc<AtomicU64>,
+        drained: Sender<()>,
+    ) -> Arc<Mutex<CapturedStream>> {
+        let captured = Arc::new(Mutex::new(CapturedStream::default()));
+        let shared = captured.clone();
+
+        thread::spawn(move || {
+            let mut buffer = [0u8; 8192];
+
+            loop {
+                match stream.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(read) => {
+                        {
+                            let mut captured = shared
+                                .lock()
+                                .unwrap_or_else(|poisoned| poisoned.into_inner());
+                            let room = MAX_CAPTURED_OUTPUT.saturating_sub(captured.bytes.len());
+                            captured.bytes.extend_from_slice(&buffer[..read.min(room)]);
+                            captured.truncated |= read > room;
+                        }
+
+                        let total = written.fetch_add(read as u64, Ordering::Relaxed) + read as u64;
+                        if limits.max_output_bytes.is_some_and(|max| total > max) {
+                            self.kill_for_limit(pid, ExceededLimit::Output);
+                        }
+                    }
+                }
+            }
+
+            // The run may have stopped waiting for it.
+            let _ = drained.send(());
+        });
+
+        captured
+    }
+
+    /// Signals the process group led by `pid`, as long as the process is still
+    /// supervised. Once it has been reaped the id may belong to someone else.
+    fn signal(&self, pid: u32, signal: libc::c_int) -> bool {
+        let running = self.lock_running();
+        if !running.contains_key(&pid) {
+            return false;
+        }
+
+        if unsafe { libc::kill(-(pid as libc::pid_t), signal) } != 0 {
+            log::error!(
+                "Failed to send signal {} to process group {}: {}",
+                signal,
+                pid,
+                std::io::Error::last_os_error()
+            );
+            return false;
+        }
+        true
+    }
+
+    fn lock_running(&self) -> std::sync::MutexGuard<'_, HashMap<u32, RunningAction>> {
+        // A panic while holding the lock leaves the map itself consistent.
+        self.running
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Where the runs and execution history of a shortcut are kept.
+pub fn logs_dir(shortcut_id: &str) -> Result<PathBuf, String> {
+    let config = AppConfig::global().lock().map_err(|e| e.to_string())?;
+    Ok(config.logs_dir.join(shortcut_id))
+}
+
+/// Writes the run and drops the oldest ones beyond `MAX_RUNS_PER_SHORTCUT`.
+fn store_run(run: &ProcessRun, file_name: &str) -> Result<(), String> {
+    let dir = logs_dir(&run.shortcut_id)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let json = serde_json::to_string_pretty(run).map_err(|e| e.to_string())?;
+    fs::write(dir.join(file_name), json).map_err(|e| e.to_string())?;
+
+    let mut runs: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    // File names start with the timestamp, so they sort chronologically.
+    runs.sort();
+
+    let excess = runs.len().saturating_sub(MAX_RUNS_PER_SHORTCUT);
+    for old_run in &runs[..excess] {
+        if let Err(e) = fs::remove_file(old_run) {
+            log::warn!("Failed to remove old run {}: {}", old_run.display(), e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn background_jobs_dont_hold_up_the_run() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo started; sleep 60 &"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .process_group(0)
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let output =
+            ProcessSupervisor::global().capture_output(&mut child, pid, RunLimits::default());
+
+        let started = Instant::now();
+        child.wait().unwrap();
+        let ((stdout, truncated), (stderr, _)) = output.finish(pid);
+        let elapsed = started.elapsed();
+        // The sleep is still in the process group.
+        unsafe { libc::kill(-(pid as libc::pid_t), libc::SIGKILL) };
+
+        assert_eq!(stdout, "started\n");
+        assert!(!truncated);
+        assert_eq!(stderr, "");
+        assert!(elapsed < Duration::from_secs(5), "waited {:?}", elapsed);
+    }
+
+    #[test]
+    fn output_is_captured_to_the_end() {
+        let mut child = Command::new("sh")
+            .args(["-c", "echo out; echo err >&2"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let pid = child.id();
+        let output =
+            ProcessSupervisor::global().capture_output(&mut child, pid, RunLimits::default());
+
+        child.wait().unwrap();
+        let ((stdout, _), (stderr, _)) = output.finish(pid);
+
+        assert_eq!(stdout, "out\n");
+        assert_eq!(stderr, "err\n");
+    }
+}
@@ -0,0 +1,163 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::definition::action::{ActionParameters, ScriptInterpreter};
+use crate::definition::script_repository::ScriptRepository;
+
+/// Builds the command for a `RunShellScript` action: the inline `script` or the
+/// managed `script_file`, run by the selected interpreter. For files without an
+/// explicit interpreter the shebang line decides, falling back to `sh`. `args`
+/// are passed to the script, never spliced into it. The file of an inline script
+/// comes along, it has to be kept until the script exits.
+pub fn script_command(
+    parameters: &ActionParameters,
+) -> Result<(Command, Option<InlineScriptFile>), String> {
+    let args = parameters.args.iter().flatten();
+
+    if let Some(name) = parameters
+        .script_file
+        .as_deref()
+        .filter(|name| !name.is_empty())
+    {
+        let path = ScriptRepository::new()?.path(name)?;
+        if !path.is_file() {
+            log::error!("Script {} not found", name);
+            return Err(format!("Script {} not found", name));
+        }
+
+        let mut command = match parameters.interpreter {
+            Some(interpreter) => file_interpreter(interpreter, parameters.shebang.as_deref())?,
+            None => match read_shebang(&path) {
+                Some(shebang) => shebang_command(&shebang)?,
+                None => Command::new("sh"),
+            },
+        };
+        command.arg(&path).args(args);
+        return Ok((command, None));
+    }
+
+    let Some(script) = &parameters.script else {
+        log::error!("No script or script_file specified for RunShellScript action");
+        return Err("No script or script_file specified for RunShellScript action".to_string());
+    };
+
+    let interpreter = parameters.interpreter.unwrap_or(ScriptInterpreter::Sh);
+    let mut inline_file = None;
+    let mut command = match interpreter_program(interpreter) {
+        Some(program) => {
+            let mut command = Command::new(program);
+            match interpreter {
+                ScriptInterpreter::Sh | ScriptInterpreter::Bash | ScriptInterpreter::Zsh => {
+                    // The extra argument is "$0", so the actual arguments start at "$1".
+                    command.arg("-c").arg(script).arg(program)
+                }
+                ScriptInterpreter::Node => command.arg("-e").arg(script),
+                _ => command.arg("-c").arg(script),
+            };
+            command
+        }
+        None => {
+            // Arbitrary interpreters can't be relied on to take inline code.
+            let mut command = shebang_command(required_shebang(parameters.shebang.as_deref())?)?;
+            let file = InlineScriptFile::write(script)?;
+            command.arg(&file.path);
+            inline_file = Some(file);
+            command
+        }
+    };
+    command.args(args);
+
+    Ok((command, inline_file))
+}
+
+/// Program for the built-in interpreters, `None` for a custom one.
+fn interpreter_program(interpreter: ScriptInterpreter) -> Option<&'static str> {
+    match interpreter {
+        ScriptInterpreter::Sh => Some("sh"),
+        ScriptInterpreter::Bash => Some("bash"),
+        ScriptInterpreter::Zsh => Some("zsh"),
+        ScriptInterpreter::Fish => Some("fish"),
+        ScriptInterpreter::Python3 => Some("python3"),
+        ScriptInterpreter::Node => Some("node"),
+        ScriptInterpreter::Custom => None,
+    }
+}
+
+fn file_interpreter(
+    interpreter: ScriptInterpreter,
+    shebang: Option<&str>,
+) -> Result<Command, String> {
+    match interpreter_program(interpreter) {
+        Some(program) => Ok(Command::new(program)),
+        None => shebang_command(required_shebang(shebang)?),
+    }
+}
+
+fn required_shebang(shebang: Option<&str>) -> Result<&str, String> {
+    shebang
+        .filter(|shebang| !shebang.trim().is_empty())
+        .ok_or_else(|| {
+            log::error!("No shebang specified for custom interpreter");
+            "No shebang specified for custom interpreter".to_string()
+        })
+}
+
+fn read_shebang(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .next()
+        .filter(|line| line.starts_with("#!"))
+        .map(str::to_string)
+}
+
+/// `#!/usr/bin/env ruby -w` (or the same without `#!`) as a command.
+fn shebang_command(shebang: &str) -> Result<Command, String> {
+    let mut parts = shebang.trim().trim_start_matches("#!").split_whitespace();
+    let Some(program) = parts.next() else {
+        return Err(format!("Invalid shebang: {}", shebang));
+    };
+
+    let mut command = Command::new(program);
+    command.args(parts);
+    Ok(command)
+}
+
+/// An inline script for a custom interpreter, written to `scripts/.inline/`
+/// for one run. The file is removed when this is dropped.
+pub struct InlineScriptFile {
+    path: PathBuf,
+}
+
+impl InlineScriptFile {
+    fn write(script: &str) -> Result<Self, String> {
+        let inline_dir = {
+            let config = AppConfig::global().lock().map_err(|e| e.to_string())?;
+            config.scripts_dir.join(".inline")
+        };
+        fs::create_dir_all(&inline_dir).map_err(|e| {
+            log::error!("Failed to create {}: {}", inline_dir.display(), e);
+            e.to_string()
+        })?;
+
+        let path = inline_dir.join(Uuid::new_v4().simple().to_string());
+        fs::write(&path, script).map_err(|e| {
+            log::error!("Failed to write {}: {}", path.display(), e);
+            e.to_string()
+        })?;
+
+        Ok(Self { path })
+    }
+}
+
+impl Drop for InlineScriptFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log::warn!("Failed to remove {}: {}", self.path.display(), e);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+use crate::definition::action::ScriptLimits;
+
+/// Environment variables a restricted script always gets, otherwise the
+/// interpreter itself can't be found.
+const ALWAYS_ALLOWED_ENV: &[&str] = &["PATH"];
+
+/// Applies the process level limits to the script command: CPU time and
+/// memory through rlimits, the niceness, network isolation and the environment
+/// allowlist. The wall-clock timeout and output size are enforced by the
+/// process supervisor. Must run before the action's own `env` is applied,
+/// since restricting the environment clears everything set so far.
+pub fn apply_script_limits(command: &mut Command, limits: &ScriptLimits) -> Result<(), String> {
+    if let Some(allowlist) = &limits.env_allowlist {
+        command.env_clear();
+        for name in ALWAYS_ALLOWED_ENV
+            .iter()
+            .copied()
+            .chain(allowlist.iter().map(String::as_str))
+        {
+            if let Some(value) = std::env::var_os(name) {
+                command.env(name, value);
+            }
+        }
+    }
+
+    if let Some(nice) = limits.nice {
+        if !(-20..=19).contains(&nice) {
+            log::error!("Invalid niceness {}, expected -20 to 19", nice);
+            return Err(format!("Invalid niceness {}, expected -20 to 19", nice));
+        }
+    }
+
+    let cpu_secs = limits.cpu_secs;
+    let memory_bytes = limits
+        .memory_mb
+        .map(|memory_mb| memory_mb.saturating_mul(1024 * 1024));
+    let nice = limits.nice;
+    let isolate_network = limits.isolate_network.unwrap_or(false);
+
+    // Only async-signal-safe calls are allowed between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            if let Some(cpu_secs) = cpu_secs {
+                // SIGXCPU at the soft limit, SIGKILL a second later.
+                set_rlimit(libc::RLIMIT_CPU, cpu_secs, cpu_secs.saturating_add(1))?;
+            }
+            if let Some(memory_bytes) = memory_bytes {
+                set_rlimit(libc::RLIMIT_AS, memory_bytes, memory_bytes)?;
+            }
+            if let Some(nice) = nice {
+                if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            if isolate_network {
+                // A new user namespace lets unprivileged users create the
+                // network namespace, which only has a loopback device (down).
+                if libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+
+    Ok(())
+}
+
+fn set_rlimit(resource: libc::__rlimit_resource_t, soft: u64, hard: u64) -> io::Result<()> {
+    let limit = libc::rlimit {
+        rlim_cur: soft as libc::rlim_t,
+        rlim_max: hard as libc::rlim_t,
+    };
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::Utc;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::definition::action::StateScope;
+
+static STATE_STORE: OnceCell<StateStore> = OnceCell::new();
+
+/// Values a toggle stores, readable like any other state.
+const TOGGLE_ON: &str = "on";
+const TOGGLE_OFF: &str = "off";
+
+/// Small amount of state that outlives a single press, kept in
+/// `data_dir/state.json`: where cycles are, and the values actions store per
+/// shortcut or globally.
+pub struct StateStore {
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct State {
+    #[serde(default)]
+    cycles: BTreeMap<String, CyclePosition>, // by shortcut id
+    #[serde(default)]
+    shortcuts: BTreeMap<String, BTreeMap<String, String>>, // by shortcut id
+    #[serde(default)]
+    global: BTreeMap<String, String>,
+}
+
+impl State {
+    fn values(&self, shortcut_id: &str, scope: StateScope) -> Option<&BTreeMap<String, String>> {
+        match scope {
+            StateScope::Shortcut => self.shortcuts.get(shortcut_id),
+            StateScope::Global => Some(&self.global),
+        }
+    }
+
+    fn values_mut(
+        &mut self,
+        shortcut_id: &str,
+        scope: StateScope,
+    ) -> &mut BTreeMap<String, String> {
+        match scope {
+            StateScope::Shortcut => self.shortcuts.entry(shortcut_id.to_string()).or_default(),
+            StateScope::Global => &mut self.global,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CyclePosition {
+    next: usize,
+    pressed_at: i64, // unix millis
+}
+
+impl StateStore {
+    pub fn global() -> &'static StateStore {
+        STATE_STORE.get_or_init(|| {
+            let path = AppConfig::global()
+                .lock()
+                .expect("Failed to lock config while loading state.")
+                .state_file
+                .clone();
+            let state = Self::load(&path);
+            StateStore {
+                path,
+                state: Mutex::new(state),
+            }
+        })
+    }
+
+    fn load(path: &PathBuf) -> State {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return State::default(),
+            Err(e) => {
+                log::error!("Failed to read state file: {}", e);
+                return State::default();
+            }
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::error!("Failed to parse state JSON: {}", e);
+            State::default()
+        })
+    }
+
+    /// Index of the step a cycling shortcut runs on this press, out of `len`
+    /// (at least one), and advances the cycle. After `reset_after` without a
+    /// press the cycle starts over from the first step.
+    pub fn next_cycle_step(
+        &self,
+        shortcut_id: &str,
+        len: usize,
+        reset_after: Option<Duration>,
+    ) -> usize {
+        let now = Utc::now().timestamp_millis();
+        let mut state = self.lock_state();
+        let index = match state.cycles.get(shortcut_id) {
+            Some(position) => {
+                let idle_ms = now.saturating_sub(position.pressed_at).max(0) as u128;
+                let expired = reset_after.is_some_and(|reset| idle_ms >= reset.as_millis());
+                if expired {
+                    0
+                } else {
+                    position.next % len
+                }
+            }
+            None => 0,
+        };
+
+        state.cycles.insert(
+            shortcut_id.to_string(),
+            CyclePosition {
+                next: (index + 1) % len,
+                pressed_at: now,
+            },
+        );
+        // The cycle keeps going in memory if the file can't be written.
+        let _ = self.persist(&state);
+
+        index
+    }
+
+    /// Value stored under `key` for the shortcut or globally.
+    pub fn get(&self, shortcut_id: &str, scope: StateScope, key: &str) -> Option<String> {
+        self.lock_state()
+            .values(shortcut_id, scope)
+            .and_then(|values| values.get(key))
+            .cloned()
+    }
+
+    /// Stores `value` under `key`, or removes the key without one. Returns
+    /// the value it replaced.
+    pub fn set(
+        &self,
+        shortcut_id: &str,
+        scope: StateScope,
+        key: &str,
+        value: Option<String>,
+    ) -> Result<Option<String>, String> {
+        validate_key(key)?;
+
+        let mut state = self.lock_state();
+        let values = state.values_mut(shortcut_id, scope);
+        let replaced = match value {
+            Some(value) => values.insert(key.to_string(), value),
+            None => values.remove(key),
+        };
+        if scope == StateScope::Shortcut && values.is_empty() {
+            state.shortcuts.remove(shortcut_id);
+        }
+        self.persist(&state)?;
+
+        Ok(replaced)
+    }
+
+    /// Flips the toggle stored under `key` and returns whether it is now on.
+    /// Flipping happens in one go, so presses in quick succession alternate.
+    pub fn flip(&self, shortcut_id: &str, scope: StateScope, key: &str) -> Result<bool, String> {
+        validate_key(key)?;
+
+        let mut state = self.lock_state();
+        let values = state.values_mut(shortcut_id, scope);
+        let on = values.get(key).map(String::as_str) != Some(TOGGLE_ON);
+        values.insert(
+            key.to_string(),
+            if on { TOGGLE_ON } else { TOGGLE_OFF }.to_string(),
+        );
+        self.persist(&state)?;
+
+        Ok(on)
+    }
+
+    /// Forgets everything stored for the shortcut, e.g. once it is deleted.
+    pub fn remove_shortcut(&self, shortcut_id: &str) -> Result<(), String> {
+        let mut state = self.lock_state();
+        let removed_cycle = state.cycles.remove(shortcut_id).is_some();
+        let removed_values = state.shortcuts.remove(shortcut_id).is_some();
+        if removed_cycle || removed_values {
+            self.persist(&state)?;
+        }
+        Ok(())
+    }
+
+    fn persist(&self, state: &State) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+        std::fs::write(&self.path, json).map_err(|e| {
+            log::error!("Failed to write state file: {}", e);
+            format!("Failed to write state file: {}", e)
+        })
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Keys must be usable in templates, i.e. `{state.<key>}`.
+fn validate_key(key: &str) -> Result<(), String> {
+    let valid = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.');
+    if !valid {
+        log::error!("Invalid state key {:?}", key);
+        return Err(format!(
+            "Invalid state key {:?}, use letters, digits, _ and .",
+            key
+        ));
+    }
+    Ok(())
+}
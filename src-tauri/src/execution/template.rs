@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+use once_cell::sync::OnceCell;
+
+use super::clipboard::{read_clipboard, read_primary_selection};
+use super::execution_shortcut::ActionResult;
+use super::state_store::StateStore;
+use super::url_opener::encode_url_component;
+use super::window_manager::WindowManager;
+use super::x11_window_manager::X11WindowManager;
+use crate::definition::action::{ActionParameters, ScriptInterpreter, StateScope};
+
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// How substituted values are written into the parameter they end up in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Raw,
+    /// Percent-encoded, for values placed inside URLs.
+    Url,
+}
+
+/// Prefix of the environment variables carrying template values into scripts.
+const SCRIPT_VARIABLE_PREFIX: &str = "SHORTCUT_VAR_";
+
+/// Resolvers are shared by the actions of a parallel group, hence `Send + Sync`.
+pub trait VariableResolver: Send + Sync {
+    /// Value of `{name}` or `{name:argument}`, `Ok(None)` when the variable is unknown.
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String>;
+}
+
+/// Expands `{variable}` and `{variable:argument}` placeholders. `{{` and `}}`
+/// produce literal braces. Braces that do not name a known variable (`${HOME}`,
+/// `awk '{print}'`) are kept as they are.
+pub fn render(
+    template: &str,
+    resolver: &dyn VariableResolver,
+    encoding: Encoding,
+) -> Result<String, String> {
+    expand(template, resolver, |value| Ok(encode(value, encoding)))
+}
+
+/// Replaces the placeholders of an inline script with references to
+/// environment variables, returned alongside the script, so values reach the
+/// interpreter as data and never as code.
+pub fn render_script(
+    script: &str,
+    interpreter: ScriptInterpreter,
+    resolver: &dyn VariableResolver,
+) -> Result<(String, HashMap<String, String>), String> {
+    let mut variables = HashMap::new();
+    let script = expand(script, resolver, |value| {
+        let name = format!("{}{}", SCRIPT_VARIABLE_PREFIX, variables.len() + 1);
+        let reference = match interpreter {
+            ScriptInterpreter::Sh
+            | ScriptInterpreter::Bash
+            | ScriptInterpreter::Zsh
+            | ScriptInterpreter::Fish => format!("\"${}\"", name),
+            ScriptInterpreter::Python3 => format!("__import__('os').environ['{}']", name),
+            ScriptInterpreter::Node => format!("process.env.{}", name),
+            ScriptInterpreter::Custom => {
+                return Err(
+                    "Template variables are not supported in custom interpreter scripts, pass them through env or args"
+                        .to_string(),
+                )
+            }
+        };
+        variables.insert(name, value.to_string());
+        Ok(reference)
+    })?;
+
+    Ok((script, variables))
+}
+
+fn expand(
+    template: &str,
+    resolver: &dyn VariableResolver,
+    mut substitute: impl FnMut(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(position) = rest.find(['{', '}']) {
+        output.push_str(&rest[..position]);
+        let brace = &rest[position..];
+
+        if brace.starts_with("{{") || brace.starts_with("}}") {
+            output.push_str(&brace[..1]);
+            rest = &brace[2..];
+            continue;
+        }
+
+        let after_dollar = output.ends_with('$');
+        let placeholder = brace
+            .strip_prefix('{')
+            .and_then(|inner| inner.find('}').map(|end| &inner[..end]))
+            .filter(|inner| !after_dollar && is_placeholder(inner));
+
+        match placeholder {
+            Some(inner) => {
+                let (name, argument) = match inner.split_once(':') {
+                    Some((name, argument)) => (name, Some(argument)),
+                    None => (inner, None),
+                };
+                match resolver.resolve(name, argument)? {
+                    Some(value) => output.push_str(&substitute(&value)?),
+                    None => output.push_str(&brace[..inner.len() + 2]),
+                }
+                rest = &brace[inner.len() + 2..];
+            }
+            None => {
+                output.push_str(&brace[..1]);
+                rest = &brace[1..];
+            }
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// `name` or `name:argument`, where the name is a dotted identifier.
+fn is_placeholder(inner: &str) -> bool {
+    let name = inner.split_once(':').map_or(inner, |(name, _)| name);
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+fn encode(value: &str, encoding: Encoding) -> String {
+    match encoding {
+        Encoding::Raw => value.to_string(),
+        // Selections often end with a newline that has no place in a URL.
+        Encoding::Url => encode_url_component(value.trim()),
+    }
+}
+
+/// Renders every templated string parameter of an action.
+pub fn render_parameters(
+    parameters: &ActionParameters,
+    resolver: &dyn VariableResolver,
+) -> Result<ActionParameters, String> {
+    let field = |name: &str, value: &Option<String>, encoding: Encoding| {
+        value
+            .as_deref()
+            .map(|value| render(value, resolver, encoding))
+            .transpose()
+            .map_err(|e| format!("{} in {}", e, name))
+    };
+
+    let mut rendered = parameters.clone();
+    rendered.path = field("path", &parameters.path, Encoding::Raw)?;
+    rendered.source_path = field("source_path", &parameters.source_path, Encoding::Raw)?;
+    rendered.target_path = field("target_path", &parameters.target_path, Encoding::Raw)?;
+    rendered.base_dir = field("base_dir", &parameters.base_dir, Encoding::Raw)?;
+    rendered.url = field("url", &parameters.url, url_encoding(parameters))?;
+    rendered.browser_profile = field(
+        "browser_profile",
+        &parameters.browser_profile,
+        Encoding::Raw,
+    )?;
+    rendered.app_name = field("app_name", &parameters.app_name, Encoding::Raw)?;
+    rendered.args = parameters
+        .args
+        .as_ref()
+        .map(|args| {
+            args.iter()
+                .map(|arg| render(arg, resolver, Encoding::Raw))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()
+        .map_err(|e| format!("{} in args", e))?;
+    rendered.cwd = field("cwd", &parameters.cwd, Encoding::Raw)?;
+    rendered.env = parameters
+        .env
+        .as_ref()
+        .map(|env| {
+            env.iter()
+                .map(|(name, value)| Ok((name.clone(), render(value, resolver, Encoding::Raw)?)))
+                .collect::<Result<HashMap<_, _>, String>>()
+        })
+        .transpose()
+        .map_err(|e| format!("{} in env", e))?;
+    rendered.text = field("text", &parameters.text, Encoding::Raw)?;
+    if let Some(script) = &parameters.script {
+        let interpreter = parameters.interpreter.unwrap_or(ScriptInterpreter::Sh);
+        let (script, variables) =
+            render_script(script, interpreter, resolver).map_err(|e| format!("{} in script", e))?;
+        rendered.script = Some(script);
+        if !variables.is_empty() {
+            rendered
+                .env
+                .get_or_insert_with(HashMap::new)
+                .extend(variables);
+        }
+    }
+    rendered.state_value = field("state_value", &parameters.state_value, Encoding::Raw)?;
+
+    Ok(rendered)
+}
+
+/// A URL that is a single placeholder, like `{prev.stdout}`, takes the value
+/// as the whole URL, which must not be encoded.
+fn url_encoding(parameters: &ActionParameters) -> Encoding {
+    let whole_placeholder = parameters
+        .url
+        .as_deref()
+        .and_then(|url| url.trim().strip_prefix('{'))
+        .and_then(|inner| inner.strip_suffix('}'))
+        .is_some_and(is_placeholder);
+
+    if whole_placeholder {
+        Encoding::Raw
+    } else {
+        Encoding::Url
+    }
+}
+
+struct FocusedWindow {
+    title: String,
+    app: String,
+}
+
+/// Variables available to the actions of a triggered shortcut. The focused
+/// window is captured up front, before actions get a chance to change focus;
+/// the selections are only read when a template asks for them.
+pub struct ShortcutVariables {
+    shortcut_name: String,
+    focused_window: Result<FocusedWindow, String>,
+    clipboard: OnceCell<Result<String, String>>,
+    primary_selection: OnceCell<Result<String, String>>,
+}
+
+impl ShortcutVariables {
+    pub fn capture(shortcut_name: &str) -> Self {
+        Self {
+            shortcut_name: shortcut_name.to_string(),
+            focused_window: Self::focused_window(),
+            clipboard: OnceCell::new(),
+            primary_selection: OnceCell::new(),
+        }
+    }
+
+    fn focused_window() -> Result<FocusedWindow, String> {
+        let window_manager = X11WindowManager::connect()?;
+        let window = window_manager
+            .active_window()?
+            .ok_or_else(|| "No focused window".to_string())?;
+
+        Ok(FocusedWindow {
+            title: window_manager.window_title(window)?,
+            app: window_manager.window_application(window)?,
+        })
+    }
+}
+
+impl VariableResolver for ShortcutVariables {
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String> {
+        let value = match name {
+            "clipboard" => self.clipboard.get_or_init(read_clipboard).clone()?,
+            "primary_selection" => self
+                .primary_selection
+                .get_or_init(read_primary_selection)
+                .clone()?,
+            "date" => format_date(argument.unwrap_or(DEFAULT_DATE_FORMAT))?,
+            "env" => {
+                let variable =
+                    argument.ok_or_else(|| "{env} requires a name, e.g. {env:HOME}".to_string())?;
+                std::env::var(variable)
+                    .map_err(|_| format!("Environment variable {} is not set", variable))?
+            }
+            "home" => dirs::home_dir()
+                .ok_or_else(|| "Failed to get home directory".to_string())?
+                .to_string_lossy()
+                .to_string(),
+            "shortcut.name" => self.shortcut_name.clone(),
+            "window.title" => self.focused_window.as_ref()?.title.clone(),
+            "window.app" => self.focused_window.as_ref()?.app.clone(),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(value))
+    }
+}
+
+/// Variables of a shortcut run by another one's `RunShortcut` action: the
+/// caller's, except for `{shortcut.name}`, which names the shortcut running.
+pub struct NestedShortcutVariables {
+    shortcut_name: String,
+    variables: Arc<dyn VariableResolver>,
+}
+
+impl NestedShortcutVariables {
+    pub fn new(shortcut_name: &str, variables: Arc<dyn VariableResolver>) -> Self {
+        Self {
+            shortcut_name: shortcut_name.to_string(),
+            variables,
+        }
+    }
+}
+
+impl VariableResolver for NestedShortcutVariables {
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String> {
+        match name {
+            "shortcut.name" => Ok(Some(self.shortcut_name.clone())),
+            _ => self.variables.resolve(name, argument),
+        }
+    }
+}
+
+/// Adds the output of the previous step to other variables: `{prev.stdout}`,
+/// without its trailing newline, and `{prev.exit_code}`.
+pub struct PreviousStepVariables<'a> {
+    previous: Option<&'a ActionResult>,
+    variables: &'a dyn VariableResolver,
+}
+
+impl<'a> PreviousStepVariables<'a> {
+    pub fn new(previous: Option<&'a ActionResult>, variables: &'a dyn VariableResolver) -> Self {
+        Self {
+            previous,
+            variables,
+        }
+    }
+}
+
+impl VariableResolver for PreviousStepVariables<'_> {
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String> {
+        match name {
+            "prev.stdout" => self
+                .previous
+                .and_then(|previous| previous.stdout.as_deref())
+                .map(|stdout| Some(stdout.trim_end_matches(['\n', '\r']).to_string()))
+                .ok_or_else(|| "The previous action has no output".to_string()),
+            "prev.exit_code" => self
+                .previous
+                .and_then(|previous| previous.exit_code)
+                .map(|code| Some(code.to_string()))
+                .ok_or_else(|| "The previous action has no exit code".to_string()),
+            _ => self.variables.resolve(name, argument),
+        }
+    }
+}
+
+/// Adds the stored state to other variables: `{state.<key>}` for the values
+/// of the running shortcut and `{global.<key>}` for the shared ones. They are
+/// read when rendered, so an action sees what earlier actions stored.
+pub struct StateVariables<'a> {
+    shortcut_id: &'a str,
+    variables: &'a dyn VariableResolver,
+}
+
+impl<'a> StateVariables<'a> {
+    pub fn new(shortcut_id: &'a str, variables: &'a dyn VariableResolver) -> Self {
+        Self {
+            shortcut_id,
+            variables,
+        }
+    }
+}
+
+impl VariableResolver for StateVariables<'_> {
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String> {
+        let (scope, key) = if let Some(key) = name.strip_prefix("state.") {
+            (StateScope::Shortcut, key)
+        } else if let Some(key) = name.strip_prefix("global.") {
+            (StateScope::Global, key)
+        } else {
+            return self.variables.resolve(name, argument);
+        };
+
+        StateStore::global()
+            .get(self.shortcut_id, scope, key)
+            .map(Some)
+            .ok_or_else(|| format!("Nothing is stored under {}", name))
+    }
+}
+
+/// Adds the answers to the shortcut's prompts to other variables as
+/// `{prompt.<name>}`.
+pub struct PromptVariables {
+    values: HashMap<String, String>,
+    variables: Arc<dyn VariableResolver>,
+}
+
+impl PromptVariables {
+    pub fn new(values: HashMap<String, String>, variables: Arc<dyn VariableResolver>) -> Self {
+        Self { values, variables }
+    }
+}
+
+impl VariableResolver for PromptVariables {
+    fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String> {
+        match name
+            .strip_prefix("prompt.")
+            .and_then(|prompt| self.values.get(prompt))
+        {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.variables.resolve(name, argument),
+        }
+    }
+}
+
+fn format_date(format: &str) -> Result<String, String> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid date format: {}", format));
+    }
+
+    Ok(Local::now()
+        .format_with_items(items.into_iter())
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    struct FakeVariables;
+
+    impl VariableResolver for FakeVariables {
+        fn resolve(&self, name: &str, argument: Option<&str>) -> Result<Option<String>, String> {
+            match (name, argument) {
+                ("clipboard", None) => Ok(Some("it's $(rm -rf ~)".to_string())),
+                ("shortcut.name", None) => Ok(Some("Notes".to_string())),
+                ("date", Some(format)) => Ok(Some(format!("date {}", format))),
+                ("prev.stdout", None) => Err("No previous step".to_string()),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    fn raw(template: &str) -> Result<String, String> {
+        render(template, &FakeVariables, Encoding::Raw)
+    }
+
+    #[test]
+    fn substitutes_variables_and_arguments() {
+        assert_eq!(
+            raw("{shortcut.name}: {date:%H:%M}").unwrap(),
+            "Notes: date %H:%M"
+        );
+    }
+
+    #[test]
+    fn double_braces_are_literal() {
+        assert_eq!(raw("{{shortcut.name}} }}").unwrap(), "{shortcut.name} }");
+    }
+
+    #[test]
+    fn keeps_shell_expansions_and_unknown_names() {
+        assert_eq!(
+            raw("${HOME} ${shortcut.name}").unwrap(),
+            "${HOME} ${shortcut.name}"
+        );
+        assert_eq!(
+            raw("awk '{print}' {unknown:x}").unwrap(),
+            "awk '{print}' {unknown:x}"
+        );
+        assert_eq!(raw("awk '{print $1}'").unwrap(), "awk '{print $1}'");
+        assert_eq!(raw("{ unclosed").unwrap(), "{ unclosed");
+    }
+
+    #[test]
+    fn reports_resolver_errors() {
+        assert_eq!(raw("{prev.stdout}").unwrap_err(), "No previous step");
+    }
+
+    #[test]
+    fn url_encoding_escapes_values() {
+        assert_eq!(
+            render(
+                "https://x/?q={shortcut.name} {date:a b}",
+                &FakeVariables,
+                Encoding::Url
+            )
+            .unwrap(),
+            "https://x/?q=Notes date%20a%20b"
+        );
+    }
+
+    #[test]
+    fn scripts_read_values_from_the_environment() {
+        let (script, env) = render_script(
+            "echo '{clipboard}' {clipboard}",
+            ScriptInterpreter::Bash,
+            &FakeVariables,
+        )
+        .unwrap();
+        assert_eq!(script, "echo '\"$SHORTCUT_VAR_1\"' \"$SHORTCUT_VAR_2\"");
+        assert_eq!(env["SHORTCUT_VAR_1"], "it's $(rm -rf ~)");
+        assert_eq!(env["SHORTCUT_VAR_2"], "it's $(rm -rf ~)");
+
+        let (script, _) = render_script(
+            "print({clipboard})",
+            ScriptInterpreter::Python3,
+            &FakeVariables,
+        )
+        .unwrap();
+        assert_eq!(script, "print(__import__('os').environ['SHORTCUT_VAR_1'])");
+
+        let (script, _) = render_script(
+            "console.log({clipboard})",
+            ScriptInterpreter::Node,
+            &FakeVariables,
+        )
+        .unwrap();
+        assert_eq!(script, "console.log(process.env.SHORTCUT_VAR_1)");
+    }
+
+    #[test]
+    fn custom_interpreter_scripts_reject_variables() {
+        assert!(render_script("{clipboard}", ScriptInterpreter::Custom, &FakeVariables).is_err());
+        assert!(render_script("{{literal}}", ScriptInterpreter::Custom, &FakeVariables).is_ok());
+    }
+
+    #[test]
+    fn script_variables_are_added_to_the_env() {
+        let parameters: ActionParameters = serde_json::from_value(json!({
+            "script": "printf %s {clipboard} \"$NAME\"",
+            "interpreter": "Bash",
+            "env": { "NAME": "{shortcut.name}" },
+        }))
+        .unwrap();
+
+        let rendered = render_parameters(&parameters, &FakeVariables).unwrap();
+        let env = rendered.env.unwrap();
+        assert_eq!(
+            rendered.script.unwrap(),
+            "printf %s \"$SHORTCUT_VAR_1\" \"$NAME\""
+        );
+        assert_eq!(env["NAME"], "Notes");
+        assert_eq!(env["SHORTCUT_VAR_1"], "it's $(rm -rf ~)");
+    }
+}
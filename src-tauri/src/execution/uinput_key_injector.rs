@@ -0,0 +1,307 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::AsRawFd;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::key_injector::{CharacterKey, KeyInjector, Modifier};
+
+// From <linux/uinput.h> and <linux/input-event-codes.h>.
+const UI_SET_EVBIT: u64 = 0x4004_5564;
+const UI_SET_KEYBIT: u64 = 0x4004_5565;
+const UI_DEV_CREATE: u64 = 0x5501;
+const UI_DEV_DESTROY: u64 = 0x5502;
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const SYN_REPORT: u16 = 0;
+const BUS_VIRTUAL: u16 = 0x06;
+const KEY_MAX_INJECTED: u16 = 255;
+// EVIOCGKEY with a bitmap large enough for every key code up to KEY_MAX.
+const EVIOCGKEY: u64 = 0x8060_4518;
+const KEY_STATE_BYTES: usize = 96;
+// Left and right Control, Shift, Alt and Meta.
+const MODIFIER_CODES: [u16; 8] = [29, 97, 42, 54, 56, 100, 125, 126];
+
+// How long the user gets to let go of the shortcut's modifiers.
+const MODIFIER_RELEASE_TIMEOUT: Duration = Duration::from_secs(1);
+const MODIFIER_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+// The compositor needs a moment to pick up a freshly created device before
+// events sent to it are delivered.
+const DEVICE_SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// Injects key events through a virtual keyboard created with /dev/uinput. Works
+/// under Wayland and on the console, but needs write access to /dev/uinput.
+pub struct UinputKeyInjector {
+    device: File,
+}
+
+impl UinputKeyInjector {
+    pub fn create() -> Result<Self, String> {
+        let device = OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/uinput")
+            .map_err(|e| {
+                log::error!("Failed to open /dev/uinput: {}", e);
+                format!("Failed to open /dev/uinput: {}", e)
+            })?;
+        let fd = device.as_raw_fd();
+
+        Self::ioctl(fd, UI_SET_EVBIT, libc::c_ulong::from(EV_KEY))?;
+        for code in 1..=KEY_MAX_INJECTED {
+            Self::ioctl(fd, UI_SET_KEYBIT, libc::c_ulong::from(code))?;
+        }
+
+        let mut setup: libc::uinput_user_dev = unsafe { std::mem::zeroed() };
+        for (target, byte) in setup.name.iter_mut().zip(b"shortcut-artisan keyboard") {
+            *target = *byte as libc::c_char;
+        }
+        setup.id.bustype = BUS_VIRTUAL;
+        setup.id.vendor = 0x1;
+        setup.id.product = 0x1;
+        setup.id.version = 1;
+
+        let mut injector = Self { device };
+        injector.write_struct(&setup)?;
+        Self::ioctl(fd, UI_DEV_CREATE, 0)?;
+        thread::sleep(DEVICE_SETTLE_DELAY);
+
+        Ok(injector)
+    }
+
+    fn ioctl(fd: i32, request: u64, value: libc::c_ulong) -> Result<(), String> {
+        if unsafe { libc::ioctl(fd, request as _, value) } < 0 {
+            let error = std::io::Error::last_os_error();
+            log::error!("uinput ioctl {:#x} failed: {}", request, error);
+            return Err(format!("uinput ioctl failed: {}", error));
+        }
+        Ok(())
+    }
+
+    fn write_struct<T>(&mut self, value: &T) -> Result<(), String> {
+        let bytes = unsafe {
+            std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>())
+        };
+        self.device.write_all(bytes).map_err(|e| e.to_string())
+    }
+
+    fn emit(&mut self, event_type: u16, code: u16, value: i32) -> Result<(), String> {
+        let event = libc::input_event {
+            time: libc::timeval {
+                tv_sec: 0,
+                tv_usec: 0,
+            },
+            type_: event_type,
+            code,
+            value,
+        };
+        self.write_struct(&event)
+    }
+}
+
+impl KeyInjector for UinputKeyInjector {
+    /// The kernel keeps key state per device, so key-up events from the virtual
+    /// keyboard can't release modifiers held on the physical one. Instead this
+    /// waits for the user to let go of them, and fails when they stay held.
+    fn release_held_modifiers(&mut self) -> Result<(), String> {
+        for modifier in Modifier::ALL {
+            self.key_event(modifier.key_name(), false)?;
+        }
+
+        let deadline = Instant::now() + MODIFIER_RELEASE_TIMEOUT;
+        loop {
+            match held_physical_modifiers() {
+                None => {
+                    log::warn!(
+                        "Cannot read the keyboard state, held modifiers may combine with the sent keys"
+                    );
+                    return Ok(());
+                }
+                Some(held) if held.is_empty() => return Ok(()),
+                Some(held) if Instant::now() >= deadline => {
+                    log::error!("Modifier keys still held: {:?}", held);
+                    return Err(
+                        "Modifier keys are still held, release them before keys are sent"
+                            .to_string(),
+                    );
+                }
+                Some(_) => thread::sleep(MODIFIER_POLL_INTERVAL),
+            }
+        }
+    }
+
+    fn key_event(&mut self, key: &str, pressed: bool) -> Result<(), String> {
+        let code =
+            linux_keycode(key).ok_or_else(|| format!("Unsupported key for uinput: {}", key))?;
+        self.emit(EV_KEY, code, i32::from(pressed))?;
+        self.emit(EV_SYN, SYN_REPORT, 0)
+    }
+
+    /// The virtual keyboard has no layout of its own; a US layout is assumed.
+    fn character_key(&mut self, character: char) -> Option<CharacterKey> {
+        const SHIFTED: &str = "~!@#$%^&*()_+{}|:\"<>?";
+        const UNSHIFTED: &str = "`1234567890-=[]\\;',./";
+
+        let (key, shift) = if character == ' ' {
+            ("space".to_string(), false)
+        } else if character.is_ascii_lowercase() || character.is_ascii_digit() {
+            (character.to_string(), false)
+        } else if character.is_ascii_uppercase() {
+            (character.to_ascii_lowercase().to_string(), true)
+        } else if let Some(position) = SHIFTED.find(character) {
+            (us_symbol_key(UNSHIFTED.as_bytes()[position] as char)?, true)
+        } else {
+            (us_symbol_key(character)?, false)
+        };
+
+        linux_keycode(&key)?;
+        Some(CharacterKey { key, shift })
+    }
+}
+
+impl Drop for UinputKeyInjector {
+    fn drop(&mut self) {
+        let _ = Self::ioctl(self.device.as_raw_fd(), UI_DEV_DESTROY, 0);
+    }
+}
+
+/// Codes of the modifier keys held on the input devices, `None` when no device
+/// can be read, usually for lack of access to /dev/input.
+fn held_physical_modifiers() -> Option<Vec<u16>> {
+    let mut readable = false;
+    let mut held = Vec::new();
+
+    for entry in fs::read_dir("/dev/input").ok()?.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("event") {
+            continue;
+        }
+        let Ok(device) = File::open(entry.path()) else {
+            continue;
+        };
+
+        let mut state = [0u8; KEY_STATE_BYTES];
+        if unsafe { libc::ioctl(device.as_raw_fd(), EVIOCGKEY as _, state.as_mut_ptr()) } < 0 {
+            continue;
+        }
+        readable = true;
+        held.extend(held_modifiers(&state));
+    }
+
+    readable.then_some(held)
+}
+
+fn held_modifiers(state: &[u8; KEY_STATE_BYTES]) -> impl Iterator<Item = u16> + '_ {
+    MODIFIER_CODES
+        .into_iter()
+        .filter(|code| state[usize::from(code / 8)] & (1 << (code % 8)) != 0)
+}
+
+fn us_symbol_key(character: char) -> Option<String> {
+    let name = match character {
+        '`' => "grave",
+        '-' => "minus",
+        '=' => "equal",
+        '[' => "bracketleft",
+        ']' => "bracketright",
+        '\\' => "backslash",
+        ';' => "semicolon",
+        '\'' => "apostrophe",
+        ',' => "comma",
+        '.' => "period",
+        '/' => "slash",
+        c if c.is_ascii_digit() => return Some(c.to_string()),
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Linux input event code for a canonical (X keysym style) key name.
+fn linux_keycode(key: &str) -> Option<u16> {
+    const LETTER_ROWS: [(&str, u16); 3] = [("qwertyuiop", 16), ("asdfghjkl", 30), ("zxcvbnm", 44)];
+
+    if key.len() == 1 {
+        let character = key.chars().next()?;
+        if let Some(digit) = character.to_digit(10) {
+            return Some(if digit == 0 { 11 } else { digit as u16 + 1 });
+        }
+        for (row, first_code) in LETTER_ROWS {
+            if let Some(position) = row.find(character) {
+                return Some(first_code + position as u16);
+            }
+        }
+    }
+
+    if let Some(number) = key.strip_prefix('F').and_then(|n| n.parse::<u16>().ok()) {
+        return match number {
+            1..=10 => Some(58 + number),
+            11 | 12 => Some(76 + number),
+            13..=24 => Some(170 + number),
+            _ => None,
+        };
+    }
+
+    let code = match key {
+        "Escape" => 1,
+        "minus" => 12,
+        "equal" => 13,
+        "BackSpace" => 14,
+        "Tab" => 15,
+        "bracketleft" => 26,
+        "bracketright" => 27,
+        "Return" => 28,
+        "Control_L" => 29,
+        "semicolon" => 39,
+        "apostrophe" => 40,
+        "grave" => 41,
+        "Shift_L" => 42,
+        "backslash" => 43,
+        "comma" => 51,
+        "period" => 52,
+        "slash" => 53,
+        "Shift_R" => 54,
+        "Alt_L" => 56,
+        "space" => 57,
+        "Caps_Lock" => 58,
+        "plus" | "KP_Add" => 78,
+        "Control_R" => 97,
+        "Print" => 99,
+        "Alt_R" => 100,
+        "Home" => 102,
+        "Up" => 103,
+        "Prior" => 104,
+        "Left" => 105,
+        "Right" => 106,
+        "End" => 107,
+        "Down" => 108,
+        "Next" => 109,
+        "Insert" => 110,
+        "Delete" => 111,
+        "XF86AudioMute" => 113,
+        "XF86AudioLowerVolume" => 114,
+        "XF86AudioRaiseVolume" => 115,
+        "Super_L" => 125,
+        "Super_R" => 126,
+        "XF86AudioNext" => 163,
+        "XF86AudioPlay" => 164,
+        "XF86AudioPrev" => 165,
+        _ => return None,
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_held_modifiers_in_the_key_state() {
+        let mut state = [0u8; KEY_STATE_BYTES];
+        // Left Control (29), A (30) and right Meta (126).
+        state[3] = 0b0110_0000;
+        state[15] = 0b0100_0000;
+
+        assert_eq!(held_modifiers(&state).collect::<Vec<_>>(), [29, 126]);
+    }
+}
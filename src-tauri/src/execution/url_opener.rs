@@ -0,0 +1,84 @@
+use std::process::Command;
+
+use url::Url;
+
+const ALLOWED_SCHEMES: [&str; 5] = ["http", "https", "ftp", "file", "mailto"];
+
+pub fn validate_url(url: &str) -> Result<Url, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| {
+        log::error!("Invalid URL {}: {}", url, e);
+        format!("Invalid URL {}: {}", url, e)
+    })?;
+
+    if !ALLOWED_SCHEMES.contains(&parsed.scheme()) {
+        log::error!("Unsupported URL scheme: {}", parsed.scheme());
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+    }
+
+    Ok(parsed)
+}
+
+/// Command that opens the URL in the default browser, or in `browser`
+/// (optionally with a specific profile) when one is given.
+pub fn open_url_command(
+    url: &Url,
+    browser: Option<&str>,
+    profile: Option<&str>,
+) -> Result<Command, String> {
+    let mut command = match browser.filter(|browser| !browser.is_empty()) {
+        Some(browser) => {
+            let mut command = Command::new(browser);
+            if let Some(profile) = profile.filter(|profile| !profile.is_empty()) {
+                command.args(profile_args(browser, profile)?);
+            }
+            command
+        }
+        None => Command::new("xdg-open"),
+    };
+
+    log::info!("Opening URL {} with {:?}", url, command.get_program());
+    command.arg(url.as_str());
+    Ok(command)
+}
+
+fn profile_args(browser: &str, profile: &str) -> Result<Vec<String>, String> {
+    let name = browser.rsplit('/').next().unwrap_or(browser).to_lowercase();
+
+    if ["firefox", "librewolf", "waterfox", "floorp"]
+        .iter()
+        .any(|family| name.starts_with(family))
+    {
+        Ok(vec!["-P".to_string(), profile.to_string()])
+    } else if [
+        "chrome",
+        "google-chrome",
+        "chromium",
+        "brave",
+        "vivaldi",
+        "microsoft-edge",
+        "opera",
+    ]
+    .iter()
+    .any(|family| name.starts_with(family))
+    {
+        Ok(vec![format!("--profile-directory={}", profile)])
+    } else {
+        Err(format!(
+            "Browser profiles are not supported for {}",
+            browser
+        ))
+    }
+}
+
+/// Percent-encodes everything except RFC 3986 unreserved characters.
+pub fn encode_url_component(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,343 @@
+use crate::definition::action::{ActionParameters, GeometryUnit, SnapPosition};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    fn contains(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        (right > left && bottom > top).then(|| Rect::new(left, top, right - left, bottom - top))
+    }
+
+    fn area(&self) -> i64 {
+        i64::from(self.width) * i64::from(self.height)
+    }
+}
+
+/// Picks the work area of the monitor the window is on: the one containing the
+/// window's center, or the one it overlaps the most when the center is off-screen.
+pub fn monitor_work_area(window: &Rect, work_areas: &[Rect]) -> Option<Rect> {
+    let center = window.center();
+
+    work_areas
+        .iter()
+        .find(|area| area.contains(center))
+        .or_else(|| {
+            work_areas.iter().max_by_key(|area| {
+                area.intersection(window)
+                    .map(|overlap| overlap.area())
+                    .unwrap_or(0)
+            })
+        })
+        .copied()
+}
+
+/// Computes the requested window frame from the action parameters. Snap positions
+/// take precedence over explicit coordinates; coordinates that are not set keep
+/// the window's current value.
+pub fn target_geometry(
+    parameters: &ActionParameters,
+    window: &Rect,
+    work_area: &Rect,
+) -> Result<Rect, String> {
+    let unit = parameters.geometry_unit.unwrap_or(GeometryUnit::Pixels);
+
+    if let Some(snap_position) = parameters.snap_position {
+        return Ok(snap_geometry(
+            snap_position,
+            parameters,
+            unit,
+            window,
+            work_area,
+        ));
+    }
+
+    if parameters.window_x.is_none()
+        && parameters.window_y.is_none()
+        && parameters.window_width.is_none()
+        && parameters.window_height.is_none()
+    {
+        return Err("No window geometry or snap position specified".to_string());
+    }
+
+    for value in [
+        parameters.window_x,
+        parameters.window_y,
+        parameters.window_width,
+        parameters.window_height,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if unit == GeometryUnit::Percent && !(0..=100).contains(&value) {
+            return Err(format!("Percentage out of range: {}", value));
+        }
+    }
+
+    let target = match unit {
+        GeometryUnit::Pixels => Rect::new(
+            parameters.window_x.unwrap_or(window.x),
+            parameters.window_y.unwrap_or(window.y),
+            parameters.window_width.unwrap_or(window.width),
+            parameters.window_height.unwrap_or(window.height),
+        ),
+        GeometryUnit::Percent => Rect::new(
+            parameters
+                .window_x
+                .map(|x| work_area.x + percent_of(work_area.width, x))
+                .unwrap_or(window.x),
+            parameters
+                .window_y
+                .map(|y| work_area.y + percent_of(work_area.height, y))
+                .unwrap_or(window.y),
+            parameters
+                .window_width
+                .map(|width| percent_of(work_area.width, width))
+                .unwrap_or(window.width),
+            parameters
+                .window_height
+                .map(|height| percent_of(work_area.height, height))
+                .unwrap_or(window.height),
+        ),
+    };
+
+    if target.width <= 0 || target.height <= 0 {
+        return Err(format!(
+            "Invalid window size: {}x{}",
+            target.width, target.height
+        ));
+    }
+
+    Ok(target)
+}
+
+fn snap_geometry(
+    snap_position: SnapPosition,
+    parameters: &ActionParameters,
+    unit: GeometryUnit,
+    window: &Rect,
+    area: &Rect,
+) -> Rect {
+    let half_width = area.width / 2;
+    let half_height = area.height / 2;
+    let third = area.width / 3;
+
+    match snap_position {
+        SnapPosition::LeftHalf => Rect::new(area.x, area.y, half_width, area.height),
+        SnapPosition::RightHalf => Rect::new(
+            area.x + half_width,
+            area.y,
+            area.width - half_width,
+            area.height,
+        ),
+        SnapPosition::TopHalf => Rect::new(area.x, area.y, area.width, half_height),
+        SnapPosition::BottomHalf => Rect::new(
+            area.x,
+            area.y + half_height,
+            area.width,
+            area.height - half_height,
+        ),
+        SnapPosition::LeftThird => Rect::new(area.x, area.y, third, area.height),
+        SnapPosition::CenterThird => Rect::new(area.x + third, area.y, third, area.height),
+        SnapPosition::RightThird => Rect::new(
+            area.x + 2 * third,
+            area.y,
+            area.width - 2 * third,
+            area.height,
+        ),
+        SnapPosition::LeftTwoThirds => Rect::new(area.x, area.y, 2 * third, area.height),
+        SnapPosition::RightTwoThirds => {
+            Rect::new(area.x + third, area.y, area.width - third, area.height)
+        }
+        SnapPosition::Center => {
+            let (width, height) = match unit {
+                GeometryUnit::Pixels => (
+                    parameters.window_width.unwrap_or(window.width),
+                    parameters.window_height.unwrap_or(window.height),
+                ),
+                GeometryUnit::Percent => (
+                    parameters
+                        .window_width
+                        .map(|width| percent_of(area.width, width))
+                        .unwrap_or(window.width),
+                    parameters
+                        .window_height
+                        .map(|height| percent_of(area.height, height))
+                        .unwrap_or(window.height),
+                ),
+            };
+            // A bogus work area can be empty, which `clamp` would panic on.
+            let width = width.min(area.width).max(1);
+            let height = height.min(area.height).max(1);
+
+            Rect::new(
+                area.x + (area.width - width) / 2,
+                area.y + (area.height - height) / 2,
+                width,
+                height,
+            )
+        }
+    }
+}
+
+fn percent_of(total: i32, percent: i32) -> i32 {
+    (i64::from(total) * i64::from(percent) / 100) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    const LEFT_MONITOR: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1080,
+    };
+    const RIGHT_MONITOR: Rect = Rect {
+        x: 1920,
+        y: 0,
+        width: 1280,
+        height: 1024,
+    };
+    const WINDOW: Rect = Rect {
+        x: 100,
+        y: 100,
+        width: 800,
+        height: 600,
+    };
+
+    fn geometry(parameters: Value, area: &Rect) -> Result<Rect, String> {
+        let parameters: ActionParameters = serde_json::from_value(parameters).unwrap();
+        target_geometry(&parameters, &WINDOW, area)
+    }
+
+    #[test]
+    fn picks_the_monitor_containing_the_center() {
+        let areas = [LEFT_MONITOR, RIGHT_MONITOR];
+
+        let window = Rect::new(1800, 100, 400, 300);
+        assert_eq!(monitor_work_area(&window, &areas), Some(RIGHT_MONITOR));
+
+        let window = Rect::new(1700, 100, 400, 300);
+        assert_eq!(monitor_work_area(&window, &areas), Some(LEFT_MONITOR));
+    }
+
+    #[test]
+    fn picks_the_most_overlapped_monitor_when_the_center_is_off_screen() {
+        let areas = [LEFT_MONITOR, RIGHT_MONITOR];
+
+        // Straddles both monitors, centered below the shorter right one.
+        let window = Rect::new(1000, 900, 1600, 260);
+        assert_eq!(monitor_work_area(&window, &areas), Some(LEFT_MONITOR));
+
+        let window = Rect::new(1800, 900, 1000, 300);
+        assert_eq!(monitor_work_area(&window, &areas), Some(RIGHT_MONITOR));
+
+        assert_eq!(monitor_work_area(&WINDOW, &[]), None);
+    }
+
+    #[test]
+    fn snaps_to_halves_and_thirds_of_the_work_area() {
+        let area = Rect::new(1920, 24, 1281, 1000);
+
+        let snap = |position: &str| geometry(json!({ "snap_position": position }), &area);
+        assert_eq!(snap("LeftHalf"), Ok(Rect::new(1920, 24, 640, 1000)));
+        assert_eq!(snap("RightHalf"), Ok(Rect::new(2560, 24, 641, 1000)));
+        assert_eq!(snap("BottomHalf"), Ok(Rect::new(1920, 524, 1281, 500)));
+        assert_eq!(snap("CenterThird"), Ok(Rect::new(2347, 24, 427, 1000)));
+        assert_eq!(snap("RightThird"), Ok(Rect::new(2774, 24, 427, 1000)));
+        assert_eq!(snap("RightTwoThirds"), Ok(Rect::new(2347, 24, 854, 1000)));
+    }
+
+    #[test]
+    fn centers_within_the_work_area() {
+        let centered = geometry(json!({ "snap_position": "Center" }), &LEFT_MONITOR);
+        assert_eq!(centered, Ok(Rect::new(560, 240, 800, 600)));
+
+        let percent = json!({
+            "snap_position": "Center",
+            "geometry_unit": "Percent",
+            "window_width": 50,
+        });
+        assert_eq!(
+            geometry(percent, &LEFT_MONITOR),
+            Ok(Rect::new(480, 240, 960, 600))
+        );
+
+        let too_large = json!({ "snap_position": "Center", "window_width": 5000 });
+        assert_eq!(
+            geometry(too_large, &LEFT_MONITOR),
+            Ok(Rect::new(0, 240, 1920, 600))
+        );
+    }
+
+    #[test]
+    fn centers_within_an_empty_work_area() {
+        let empty = Rect::new(0, 0, 0, 0);
+
+        let centered = geometry(json!({ "snap_position": "Center" }), &empty);
+        assert_eq!(centered, Ok(Rect::new(0, 0, 1, 1)));
+    }
+
+    #[test]
+    fn keeps_coordinates_that_are_not_set() {
+        let moved = geometry(
+            json!({ "window_x": 10, "window_height": 300 }),
+            &LEFT_MONITOR,
+        );
+        assert_eq!(moved, Ok(Rect::new(10, 100, 800, 300)));
+    }
+
+    #[test]
+    fn converts_percentages_of_the_work_area() {
+        let parameters = json!({
+            "geometry_unit": "Percent",
+            "window_x": 50,
+            "window_width": 25,
+        });
+        assert_eq!(
+            geometry(parameters, &RIGHT_MONITOR),
+            Ok(Rect::new(2560, 100, 320, 600))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_geometry() {
+        assert!(geometry(json!({}), &LEFT_MONITOR).is_err());
+        assert!(geometry(
+            json!({ "geometry_unit": "Percent", "window_x": 101 }),
+            &LEFT_MONITOR
+        )
+        .is_err());
+        assert!(geometry(json!({ "window_width": 0 }), &LEFT_MONITOR).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use super::desktop_entry::application_names;
+use super::process_lookup::find_processes;
+use super::window_geometry::{monitor_work_area, target_geometry, Rect};
+use crate::definition::action::{ActionParameters, ActionType};
+
+pub type WindowId = u64;
+
+/// Minimal set of window operations the window and application actions rely on.
+/// The X11 implementation talks to an EWMH-compliant window manager; tests can
+/// provide a fake implementation instead.
+pub trait WindowManager {
+    fn active_window(&self) -> Result<Option<WindowId>, String>;
+    /// Windows belonging to `app_name`, ordered from bottom to top of the stack.
+    fn find_application_windows(&self, app_name: &str) -> Result<Vec<WindowId>, String>;
+    /// Windows owned by any of the given processes, ordered from bottom to top.
+    fn find_process_windows(&self, pids: &[u32]) -> Result<Vec<WindowId>, String>;
+    fn window_title(&self, window: WindowId) -> Result<String, String>;
+    /// Application the window belongs to: its WM_CLASS class, or the process name.
+    fn window_application(&self, window: WindowId) -> Result<String, String>;
+    fn activate_window(&self, window: WindowId) -> Result<(), String>;
+    fn close_window(&self, window: WindowId) -> Result<(), String>;
+    fn minimize_window(&self, window: WindowId) -> Result<(), String>;
+    fn maximize_window(&self, window: WindowId) -> Result<(), String>;
+    /// Outer frame of the window, decorations included, in desktop coordinates.
+    fn window_frame(&self, window: WindowId) -> Result<Rect, String>;
+    /// Usable area (without panels and docks) of every connected monitor.
+    fn monitor_work_areas(&self) -> Result<Vec<Rect>, String>;
+    fn move_resize_window(&self, window: WindowId, frame: Rect) -> Result<(), String>;
+}
+
+pub fn apply_window_action<W: WindowManager + ?Sized>(
+    window_manager: &W,
+    action_type: &ActionType,
+    parameters: &ActionParameters,
+) -> Result<(), String> {
+    let app_name = parameters.app_name.as_deref();
+
+    match action_type {
+        ActionType::QuitApplication => {
+            for window in application_windows(window_manager, action_type, app_name)? {
+                window_manager.close_window(window)?;
+            }
+            Ok(())
+        }
+        ActionType::HideApplication => {
+            for window in application_windows(window_manager, action_type, app_name)? {
+                window_manager.minimize_window(window)?;
+            }
+            Ok(())
+        }
+        ActionType::FocusApplication => {
+            let windows = application_windows(window_manager, action_type, app_name)?;
+            match windows.last() {
+                Some(window) => window_manager.activate_window(*window),
+                None => Ok(()),
+            }
+        }
+        ActionType::MinimizeWindow => {
+            let window = target_window(window_manager, app_name)?;
+            window_manager.minimize_window(window)
+        }
+        ActionType::MaximizeWindow => {
+            let window = target_window(window_manager, app_name)?;
+            window_manager.maximize_window(window)
+        }
+        ActionType::MoveResizeWindow => {
+            let window = target_window(window_manager, app_name)?;
+            let frame = window_manager.window_frame(window)?;
+            let work_areas = window_manager.monitor_work_areas()?;
+            let work_area = monitor_work_area(&frame, &work_areas)
+                .ok_or_else(|| "No monitors found".to_string())?;
+
+            let target = target_geometry(parameters, &frame, &work_area)?;
+            log::debug!("Moving window {} from {:?} to {:?}", window, frame, target);
+            window_manager.move_resize_window(window, target)
+        }
+        _ => Err(format!("{:?} is not a window action", action_type)),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToggleOutcome {
+    Focused,
+    Hidden,
+    /// No windows were found, the application has to be launched.
+    NotRunning,
+}
+
+/// Hides the application when one of its windows has focus, focuses its
+/// topmost window otherwise. Windows are matched by WM_CLASS first, then by the
+/// processes whose executable matches, trying each of the `application_names`
+/// of `app_name`.
+pub fn toggle_application<W: WindowManager + ?Sized>(
+    window_manager: &W,
+    app_name: &str,
+) -> Result<ToggleOutcome, String> {
+    let names = application_names(app_name);
+    let mut windows = Vec::new();
+    for name in &names {
+        windows = window_manager.find_application_windows(name)?;
+        if !windows.is_empty() {
+            break;
+        }
+    }
+    if windows.is_empty() {
+        let pids: Vec<u32> = names.iter().flat_map(|name| find_processes(name)).collect();
+        if !pids.is_empty() {
+            windows = window_manager.find_process_windows(&pids)?;
+            log::debug!("{} runs as {:?} with windows {:?}", app_name, pids, windows);
+        }
+    }
+
+    let Some(topmost) = windows.last().copied() else {
+        return Ok(ToggleOutcome::NotRunning);
+    };
+
+    let active = window_manager.active_window()?;
+    if active.is_some_and(|active| windows.contains(&active)) {
+        for window in windows {
+            window_manager.minimize_window(window)?;
+        }
+        Ok(ToggleOutcome::Hidden)
+    } else {
+        window_manager.activate_window(topmost)?;
+        Ok(ToggleOutcome::Focused)
+    }
+}
+
+fn application_windows<W: WindowManager + ?Sized>(
+    window_manager: &W,
+    action_type: &ActionType,
+    app_name: Option<&str>,
+) -> Result<Vec<WindowId>, String> {
+    let app_name = app_name.ok_or_else(|| {
+        log::error!("No app_name specified for {:?} action", action_type);
+        format!("No app_name specified for {:?} action", action_type)
+    })?;
+
+    let windows = window_manager.find_application_windows(app_name)?;
+    if windows.is_empty() {
+        log::warn!("No windows found for application: {}", app_name);
+        return Err(format!("No windows found for application {}", app_name));
+    }
+
+    Ok(windows)
+}
+
+/// Window matched by `app_name` (topmost one) or the focused window otherwise.
+fn target_window<W: WindowManager + ?Sized>(
+    window_manager: &W,
+    app_name: Option<&str>,
+) -> Result<WindowId, String> {
+    match app_name.filter(|name| !name.is_empty()) {
+        Some(app_name) => window_manager
+            .find_application_windows(app_name)?
+            .last()
+            .copied()
+            .ok_or_else(|| format!("No windows found for application {}", app_name)),
+        None => window_manager
+            .active_window()?
+            .ok_or_else(|| "No focused window".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Records the operations applied to its windows.
+    #[derive(Default)]
+    struct FakeWindowManager {
+        applications: Vec<(&'static str, Vec<WindowId>)>, // windows bottom to top
+        active: Option<WindowId>,
+        calls: RefCell<Vec<(&'static str, WindowId)>>,
+    }
+
+    impl FakeWindowManager {
+        fn with_firefox() -> Self {
+            Self {
+                applications: vec![("firefox", vec![1, 2]), ("code", vec![3])],
+                active: Some(3),
+                ..Default::default()
+            }
+        }
+
+        fn record(&self, operation: &'static str, window: WindowId) -> Result<(), String> {
+            self.calls.borrow_mut().push((operation, window));
+            Ok(())
+        }
+
+        fn calls(&self) -> Vec<(&'static str, WindowId)> {
+            self.calls.borrow().clone()
+        }
+    }
+
+    impl WindowManager for FakeWindowManager {
+        fn active_window(&self) -> Result<Option<WindowId>, String> {
+            Ok(self.active)
+        }
+
+        fn find_application_windows(&self, app_name: &str) -> Result<Vec<WindowId>, String> {
+            Ok(self
+                .applications
+                .iter()
+                .filter(|(name, _)| name.eq_ignore_ascii_case(app_name))
+                .flat_map(|(_, windows)| windows.iter().copied())
+                .collect())
+        }
+
+        fn find_process_windows(&self, _pids: &[u32]) -> Result<Vec<WindowId>, String> {
+            Ok(Vec::new())
+        }
+
+        fn window_title(&self, window: WindowId) -> Result<String, String> {
+            Ok(format!("Window {}", window))
+        }
+
+        fn window_application(&self, window: WindowId) -> Result<String, String> {
+            self.applications
+                .iter()
+                .find(|(_, windows)| windows.contains(&window))
+                .map(|(name, _)| name.to_string())
+                .ok_or_else(|| format!("Unknown window {}", window))
+        }
+
+        fn activate_window(&self, window: WindowId) -> Result<(), String> {
+            self.record("activate", window)
+        }
+
+        fn close_window(&self, window: WindowId) -> Result<(), String> {
+            self.record("close", window)
+        }
+
+        fn minimize_window(&self, window: WindowId) -> Result<(), String> {
+            self.record("minimize", window)
+        }
+
+        fn maximize_window(&self, window: WindowId) -> Result<(), String> {
+            self.record("maximize", window)
+        }
+
+        fn window_frame(&self, _window: WindowId) -> Result<Rect, String> {
+            Err("Not supported by the fake window manager".to_string())
+        }
+
+        fn monitor_work_areas(&self) -> Result<Vec<Rect>, String> {
+            Ok(Vec::new())
+        }
+
+        fn move_resize_window(&self, window: WindowId, _frame: Rect) -> Result<(), String> {
+            self.record("move_resize", window)
+        }
+    }
+
+    fn parameters(app_name: Option<&str>) -> ActionParameters {
+        serde_json::from_value(serde_json::json!({ "app_name": app_name }))
+            .expect("all parameters are optional")
+    }
+
+    fn apply(
+        window_manager: &FakeWindowManager,
+        action_type: ActionType,
+        app_name: Option<&str>,
+    ) -> Result<(), String> {
+        apply_window_action(window_manager, &action_type, &parameters(app_name))
+    }
+
+    #[test]
+    fn quit_closes_every_window_of_the_application() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        apply(
+            &window_manager,
+            ActionType::QuitApplication,
+            Some("firefox"),
+        )
+        .unwrap();
+
+        assert_eq!(window_manager.calls(), vec![("close", 1), ("close", 2)]);
+    }
+
+    #[test]
+    fn hide_minimizes_every_window_of_the_application() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        apply(
+            &window_manager,
+            ActionType::HideApplication,
+            Some("Firefox"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            window_manager.calls(),
+            vec![("minimize", 1), ("minimize", 2)]
+        );
+    }
+
+    #[test]
+    fn focus_activates_the_topmost_window() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        apply(
+            &window_manager,
+            ActionType::FocusApplication,
+            Some("firefox"),
+        )
+        .unwrap();
+
+        assert_eq!(window_manager.calls(), vec![("activate", 2)]);
+    }
+
+    #[test]
+    fn minimize_targets_the_application_or_the_focused_window() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        apply(&window_manager, ActionType::MinimizeWindow, Some("firefox")).unwrap();
+        apply(&window_manager, ActionType::MinimizeWindow, None).unwrap();
+
+        assert_eq!(
+            window_manager.calls(),
+            vec![("minimize", 2), ("minimize", 3)]
+        );
+    }
+
+    #[test]
+    fn maximize_targets_the_application_or_the_focused_window() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        apply(&window_manager, ActionType::MaximizeWindow, Some("firefox")).unwrap();
+        apply(&window_manager, ActionType::MaximizeWindow, Some("")).unwrap();
+
+        assert_eq!(
+            window_manager.calls(),
+            vec![("maximize", 2), ("maximize", 3)]
+        );
+    }
+
+    #[test]
+    fn fails_when_the_application_has_no_windows() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        for action_type in [
+            ActionType::QuitApplication,
+            ActionType::HideApplication,
+            ActionType::FocusApplication,
+            ActionType::MinimizeWindow,
+            ActionType::MaximizeWindow,
+        ] {
+            let error = apply(&window_manager, action_type, Some("gimp")).unwrap_err();
+            assert_eq!(error, "No windows found for application gimp");
+        }
+        assert!(window_manager.calls().is_empty());
+    }
+
+    #[test]
+    fn fails_without_a_focused_window() {
+        let window_manager = FakeWindowManager {
+            active: None,
+            ..FakeWindowManager::with_firefox()
+        };
+
+        let error = apply(&window_manager, ActionType::MaximizeWindow, None).unwrap_err();
+
+        assert_eq!(error, "No focused window");
+    }
+
+    #[test]
+    fn application_actions_require_an_app_name() {
+        let window_manager = FakeWindowManager::with_firefox();
+
+        assert!(apply(&window_manager, ActionType::QuitApplication, None).is_err());
+        assert!(window_manager.calls().is_empty());
+    }
+}
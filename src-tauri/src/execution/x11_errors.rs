@@ -0,0 +1,99 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::sync::{Mutex, MutexGuard};
+
+use once_cell::sync::OnceCell;
+use x11_dl::xlib::{self, Display, XErrorEvent, Xlib};
+
+type ErrorHandler = unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int;
+
+// Displays opened by the actions, with the first error each one got since it
+// was last synced.
+static DISPLAYS: Mutex<Vec<(usize, Option<XError>)>> = Mutex::new(Vec::new());
+// The handler installed before ours, usually GDK's, gets every other display.
+static PREVIOUS_HANDLER: OnceCell<Option<ErrorHandler>> = OnceCell::new();
+
+#[derive(Debug, Clone, Copy)]
+struct XError {
+    error_code: u8,
+    request_code: u8,
+    resource_id: u64,
+}
+
+/// Keeps X errors on `display` from reaching Xlib's default handler, which
+/// exits the process. A window may disappear between two requests, which is
+/// no reason to take the app down; `sync` reports such errors instead.
+pub fn trap_errors(xlib: &Xlib, display: *mut Display) {
+    PREVIOUS_HANDLER.get_or_init(|| unsafe { (xlib.XSetErrorHandler)(Some(handle_error)) });
+    lock_displays().push((display as usize, None));
+}
+
+/// Forgets a display trapped with `trap_errors`, once it is closed.
+pub fn release_errors(display: *mut Display) {
+    lock_displays().retain(|(own, _)| *own != display as usize);
+}
+
+/// Waits until the server has processed every request sent on `display`, and
+/// returns the first error one of them caused.
+pub fn sync(xlib: &Xlib, display: *mut Display) -> Result<(), String> {
+    unsafe { (xlib.XSync)(display, xlib::False) };
+
+    let error = lock_displays()
+        .iter_mut()
+        .find(|(own, _)| *own == display as usize)
+        .and_then(|(_, error)| error.take());
+    let Some(error) = error else {
+        return Ok(());
+    };
+
+    let mut text: [c_char; 256] = [0; 256];
+    unsafe {
+        (xlib.XGetErrorText)(
+            display,
+            c_int::from(error.error_code),
+            text.as_mut_ptr(),
+            text.len() as c_int,
+        )
+    };
+    let text = unsafe { CStr::from_ptr(text.as_ptr()) }.to_string_lossy();
+
+    log::warn!(
+        "X error {} for resource {:#x} (request {})",
+        text,
+        error.resource_id,
+        error.request_code
+    );
+    Err(format!(
+        "X error {} for resource {:#x}",
+        text, error.resource_id
+    ))
+}
+
+unsafe extern "C" fn handle_error(display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    {
+        let mut displays = lock_displays();
+        if let Some((_, error)) = displays
+            .iter_mut()
+            .find(|(own, _)| *own == display as usize)
+        {
+            let event = &*event;
+            error.get_or_insert(XError {
+                error_code: event.error_code,
+                request_code: event.request_code,
+                resource_id: event.resourceid,
+            });
+            return 0;
+        }
+    }
+
+    match PREVIOUS_HANDLER.get().copied().flatten() {
+        Some(previous) => previous(display, event),
+        None => 0,
+    }
+}
+
+fn lock_displays() -> MutexGuard<'static, Vec<(usize, Option<XError>)>> {
+    DISPLAYS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,521 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_int, c_long, c_uchar, c_uint, c_ulong};
+use std::ptr;
+
+use x11_dl::xinerama;
+use x11_dl::xlib::{self, Atom, Display, Window, Xlib};
+
+use super::window_geometry::Rect;
+use super::window_manager::{WindowId, WindowManager};
+use super::x11_errors::{release_errors, sync, trap_errors};
+
+// EWMH source indication: requests come from a pager-like tool, not an application.
+const SOURCE_PAGER: c_long = 2;
+const NET_WM_STATE_REMOVE: c_long = 0;
+const NET_WM_STATE_ADD: c_long = 1;
+// _NET_MOVERESIZE_WINDOW flags: x, y, width and height are all present.
+const MOVERESIZE_ALL_FIELDS: c_long = 0b1111 << 8;
+
+pub struct X11WindowManager {
+    xlib: Xlib,
+    display: *mut Display,
+    root: Window,
+}
+
+impl X11WindowManager {
+    pub fn connect() -> Result<Self, String> {
+        let xlib = Xlib::open().map_err(|e| {
+            log::error!("Failed to load Xlib: {}", e);
+            e.to_string()
+        })?;
+
+        let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+        if display.is_null() {
+            log::error!("Failed to open X display");
+            return Err("Failed to open X display (is DISPLAY set?)".to_string());
+        }
+
+        trap_errors(&xlib, display);
+        let root = unsafe { (xlib.XDefaultRootWindow)(display) };
+
+        Ok(Self {
+            xlib,
+            display,
+            root,
+        })
+    }
+
+    /// Waits for the requests sent so far, failing when one of them did.
+    fn sync(&self) -> Result<(), String> {
+        sync(&self.xlib, self.display)
+    }
+
+    fn atom(&self, name: &str) -> Result<Atom, String> {
+        let name = CString::new(name).map_err(|e| e.to_string())?;
+        Ok(unsafe { (self.xlib.XInternAtom)(self.display, name.as_ptr(), xlib::False) })
+    }
+
+    /// Reads a 32-bit format property (windows, cardinals, atoms) as a list of values.
+    fn get_long_property(
+        &self,
+        window: Window,
+        property: &str,
+        property_type: Atom,
+    ) -> Result<Vec<WindowId>, String> {
+        let property_name = property;
+        let property = self.atom(property)?;
+        let mut actual_type: Atom = 0;
+        let mut actual_format: c_int = 0;
+        let mut item_count: c_ulong = 0;
+        let mut bytes_after: c_ulong = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+
+        let status = unsafe {
+            (self.xlib.XGetWindowProperty)(
+                self.display,
+                window,
+                property,
+                0,
+                c_long::MAX,
+                xlib::False,
+                property_type,
+                &mut actual_type,
+                &mut actual_format,
+                &mut item_count,
+                &mut bytes_after,
+                &mut data,
+            )
+        };
+        // The window may be gone by now.
+        self.sync().map_err(|e| {
+            format!(
+                "Failed to read {} of window {}: {}",
+                property_name, window, e
+            )
+        })?;
+
+        if status != xlib::Success as c_int || data.is_null() {
+            return Ok(Vec::new());
+        }
+
+        // Xlib hands out 32-bit format properties as arrays of C longs.
+        let values = if actual_format == 32 {
+            unsafe { std::slice::from_raw_parts(data as *const c_ulong, item_count as usize) }
+                .iter()
+                .map(|value| *value as WindowId)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        unsafe { (self.xlib.XFree)(data as *mut _) };
+        Ok(values)
+    }
+
+    /// Reads an 8-bit format property (strings) as text.
+    fn get_string_property(
+        &self,
+        window: Window,
+        property: &str,
+        property_type: Atom,
+    ) -> Result<Option<String>, String> {
+        let property_name = property;
+        let property = self.atom(property)?;
+        let mut actual_type: Atom = 0;
+        let mut actual_format: c_int = 0;
+        let mut item_count: c_ulong = 0;
+        let mut bytes_after: c_ulong = 0;
+        let mut data: *mut c_uchar = ptr::null_mut();
+
+        let status = unsafe {
+            (self.xlib.XGetWindowProperty)(
+                self.display,
+                window,
+                property,
+                0,
+                c_long::MAX,
+                xlib::False,
+                property_type,
+                &mut actual_type,
+                &mut actual_format,
+                &mut item_count,
+                &mut bytes_after,
+                &mut data,
+            )
+        };
+        // The window may be gone by now.
+        self.sync().map_err(|e| {
+            format!(
+                "Failed to read {} of window {}: {}",
+                property_name, window, e
+            )
+        })?;
+
+        if status != xlib::Success as c_int || data.is_null() {
+            return Ok(None);
+        }
+
+        let text = (actual_format == 8).then(|| {
+            let bytes = unsafe { std::slice::from_raw_parts(data, item_count as usize) };
+            String::from_utf8_lossy(bytes).to_string()
+        });
+
+        unsafe { (self.xlib.XFree)(data as *mut _) };
+        Ok(text)
+    }
+
+    fn window_class(&self, window: Window) -> Option<(String, String)> {
+        let mut hint = xlib::XClassHint {
+            res_name: ptr::null_mut(),
+            res_class: ptr::null_mut(),
+        };
+
+        let status = unsafe { (self.xlib.XGetClassHint)(self.display, window, &mut hint) };
+        if self.sync().is_err() || status == 0 {
+            return None;
+        }
+
+        let read = |value: *mut std::os::raw::c_char| {
+            if value.is_null() {
+                return String::new();
+            }
+            let text = unsafe { CStr::from_ptr(value) }
+                .to_string_lossy()
+                .to_string();
+            unsafe { (self.xlib.XFree)(value as *mut _) };
+            text
+        };
+
+        Some((read(hint.res_name), read(hint.res_class)))
+    }
+
+    fn window_process_name(&self, window: Window) -> Option<String> {
+        let pid = *self
+            .get_long_property(window, "_NET_WM_PID", xlib::XA_CARDINAL)
+            .ok()?
+            .first()?;
+
+        std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|comm| comm.trim().to_string())
+    }
+
+    fn window_matches(&self, window: Window, app_name: &str) -> bool {
+        if let Some((name, class)) = self.window_class(window) {
+            if name.eq_ignore_ascii_case(app_name) || class.eq_ignore_ascii_case(app_name) {
+                return true;
+            }
+        }
+
+        self.window_process_name(window)
+            .map(|process| process.eq_ignore_ascii_case(app_name))
+            .unwrap_or(false)
+    }
+
+    /// Decoration sizes as (left, right, top, bottom), zero for undecorated windows.
+    fn frame_extents(&self, window: Window) -> Result<(i32, i32, i32, i32), String> {
+        let extents = self.get_long_property(window, "_NET_FRAME_EXTENTS", xlib::XA_CARDINAL)?;
+        match extents.as_slice() {
+            [left, right, top, bottom] => {
+                Ok((*left as i32, *right as i32, *top as i32, *bottom as i32))
+            }
+            _ => Ok((0, 0, 0, 0)),
+        }
+    }
+
+    fn monitors(&self) -> Vec<Rect> {
+        if let Ok(xinerama) = xinerama::Xlib::open() {
+            unsafe {
+                if (xinerama.XineramaIsActive)(self.display) != 0 {
+                    let mut count: c_int = 0;
+                    let screens = (xinerama.XineramaQueryScreens)(self.display, &mut count);
+                    if !screens.is_null() {
+                        let monitors = std::slice::from_raw_parts(screens, count as usize)
+                            .iter()
+                            .map(|screen| {
+                                Rect::new(
+                                    i32::from(screen.x_org),
+                                    i32::from(screen.y_org),
+                                    i32::from(screen.width),
+                                    i32::from(screen.height),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        (self.xlib.XFree)(screens as *mut _);
+
+                        if !monitors.is_empty() {
+                            return monitors;
+                        }
+                    }
+                }
+            }
+        } else {
+            log::warn!("Xinerama is not available, treating the screen as a single monitor");
+        }
+
+        unsafe {
+            let screen = (self.xlib.XDefaultScreen)(self.display);
+            vec![Rect::new(
+                0,
+                0,
+                (self.xlib.XDisplayWidth)(self.display, screen),
+                (self.xlib.XDisplayHeight)(self.display, screen),
+            )]
+        }
+    }
+
+    /// _NET_WORKAREA of the current desktop. EWMH only exposes a single rectangle
+    /// spanning all monitors, so it is intersected with each monitor afterwards.
+    fn desktop_work_area(&self) -> Result<Option<Rect>, String> {
+        let desktop = self
+            .get_long_property(self.root, "_NET_CURRENT_DESKTOP", xlib::XA_CARDINAL)?
+            .first()
+            .copied()
+            .unwrap_or(0) as usize;
+        let work_areas = self.get_long_property(self.root, "_NET_WORKAREA", xlib::XA_CARDINAL)?;
+
+        Ok(work_areas.chunks_exact(4).nth(desktop).map(|area| {
+            Rect::new(
+                area[0] as i32,
+                area[1] as i32,
+                area[2] as i32,
+                area[3] as i32,
+            )
+        }))
+    }
+
+    fn send_client_message(
+        &self,
+        window: Window,
+        message_type: &str,
+        data: [c_long; 5],
+    ) -> Result<(), String> {
+        let message = xlib::XClientMessageEvent {
+            type_: xlib::ClientMessage,
+            serial: 0,
+            send_event: xlib::True,
+            display: self.display,
+            window,
+            message_type: self.atom(message_type)?,
+            format: 32,
+            data: xlib::ClientMessageData::from(data),
+        };
+        let mut event = xlib::XEvent::from(message);
+
+        let status = unsafe {
+            (self.xlib.XSendEvent)(
+                self.display,
+                self.root,
+                xlib::False,
+                xlib::SubstructureRedirectMask | xlib::SubstructureNotifyMask,
+                &mut event,
+            )
+        };
+        self.sync()?;
+
+        if status == 0 {
+            log::error!("Failed to send {} to window {}", message_type, window);
+            return Err(format!(
+                "Failed to send {} to window {}",
+                message_type, window
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl WindowManager for X11WindowManager {
+    fn active_window(&self) -> Result<Option<WindowId>, String> {
+        let windows = self.get_long_property(self.root, "_NET_ACTIVE_WINDOW", xlib::XA_WINDOW)?;
+        Ok(windows.first().copied().filter(|window| *window != 0))
+    }
+
+    fn find_application_windows(&self, app_name: &str) -> Result<Vec<WindowId>, String> {
+        let windows =
+            self.get_long_property(self.root, "_NET_CLIENT_LIST_STACKING", xlib::XA_WINDOW)?;
+
+        Ok(windows
+            .into_iter()
+            .filter(|window| self.window_matches(*window as Window, app_name))
+            .collect())
+    }
+
+    fn find_process_windows(&self, pids: &[u32]) -> Result<Vec<WindowId>, String> {
+        let windows =
+            self.get_long_property(self.root, "_NET_CLIENT_LIST_STACKING", xlib::XA_WINDOW)?;
+
+        Ok(windows
+            .into_iter()
+            .filter(|window| {
+                self.get_long_property(*window as Window, "_NET_WM_PID", xlib::XA_CARDINAL)
+                    .ok()
+                    .and_then(|pid| pid.first().copied())
+                    .is_some_and(|pid| pids.iter().any(|wanted| u64::from(*wanted) == pid))
+            })
+            .collect())
+    }
+
+    fn window_title(&self, window: WindowId) -> Result<String, String> {
+        let utf8_string = self.atom("UTF8_STRING")?;
+        let title = match self.get_string_property(window as Window, "_NET_WM_NAME", utf8_string)? {
+            Some(title) => Some(title),
+            None => self.get_string_property(window as Window, "WM_NAME", xlib::XA_STRING)?,
+        };
+
+        Ok(title.unwrap_or_default())
+    }
+
+    fn window_application(&self, window: WindowId) -> Result<String, String> {
+        self.window_class(window as Window)
+            .map(|(_, class)| class)
+            .filter(|class| !class.is_empty())
+            .or_else(|| self.window_process_name(window as Window))
+            .ok_or_else(|| format!("Failed to identify the application of window {}", window))
+    }
+
+    fn activate_window(&self, window: WindowId) -> Result<(), String> {
+        log::debug!("Activating window {}", window);
+        self.send_client_message(
+            window as Window,
+            "_NET_ACTIVE_WINDOW",
+            [SOURCE_PAGER, xlib::CurrentTime as c_long, 0, 0, 0],
+        )
+    }
+
+    fn close_window(&self, window: WindowId) -> Result<(), String> {
+        log::debug!("Closing window {}", window);
+        self.send_client_message(
+            window as Window,
+            "_NET_CLOSE_WINDOW",
+            [xlib::CurrentTime as c_long, SOURCE_PAGER, 0, 0, 0],
+        )
+    }
+
+    fn minimize_window(&self, window: WindowId) -> Result<(), String> {
+        log::debug!("Minimizing window {}", window);
+        let status = unsafe {
+            let screen = (self.xlib.XDefaultScreen)(self.display);
+            (self.xlib.XIconifyWindow)(self.display, window as Window, screen)
+        };
+        self.sync()?;
+
+        if status == 0 {
+            log::error!("Failed to minimize window {}", window);
+            return Err(format!("Failed to minimize window {}", window));
+        }
+        Ok(())
+    }
+
+    fn maximize_window(&self, window: WindowId) -> Result<(), String> {
+        log::debug!("Maximizing window {}", window);
+        let vertical = self.atom("_NET_WM_STATE_MAXIMIZED_VERT")? as c_long;
+        let horizontal = self.atom("_NET_WM_STATE_MAXIMIZED_HORZ")? as c_long;
+        self.send_client_message(
+            window as Window,
+            "_NET_WM_STATE",
+            [NET_WM_STATE_ADD, vertical, horizontal, SOURCE_PAGER, 0],
+        )
+    }
+
+    fn window_frame(&self, window: WindowId) -> Result<Rect, String> {
+        let window = window as Window;
+        let mut root: Window = 0;
+        let (mut x, mut y): (c_int, c_int) = (0, 0);
+        let (mut width, mut height, mut border, mut depth): (c_uint, c_uint, c_uint, c_uint) =
+            (0, 0, 0, 0);
+        let mut child: Window = 0;
+
+        let (geometry, translated) = unsafe {
+            let geometry = (self.xlib.XGetGeometry)(
+                self.display,
+                window,
+                &mut root,
+                &mut x,
+                &mut y,
+                &mut width,
+                &mut height,
+                &mut border,
+                &mut depth,
+            );
+            let translated = (self.xlib.XTranslateCoordinates)(
+                self.display,
+                window,
+                self.root,
+                0,
+                0,
+                &mut x,
+                &mut y,
+                &mut child,
+            );
+            (geometry, translated)
+        };
+        self.sync()?;
+        if geometry == 0 {
+            return Err(format!("Failed to read geometry of window {}", window));
+        }
+        if translated == 0 {
+            return Err(format!(
+                "Failed to translate coordinates of window {}",
+                window
+            ));
+        }
+
+        let (left, right, top, bottom) = self.frame_extents(window)?;
+        Ok(Rect::new(
+            x - left,
+            y - top,
+            width as i32 + left + right,
+            height as i32 + top + bottom,
+        ))
+    }
+
+    fn monitor_work_areas(&self) -> Result<Vec<Rect>, String> {
+        let monitors = self.monitors();
+        let work_area = self.desktop_work_area()?;
+
+        Ok(monitors
+            .iter()
+            .map(|monitor| {
+                work_area
+                    .and_then(|area| monitor.intersection(&area))
+                    .unwrap_or(*monitor)
+            })
+            .collect())
+    }
+
+    fn move_resize_window(&self, window: WindowId, frame: Rect) -> Result<(), String> {
+        log::debug!("Moving window {} to {:?}", window, frame);
+        let window = window as Window;
+
+        // A maximized window ignores geometry requests, so drop that state first.
+        let vertical = self.atom("_NET_WM_STATE_MAXIMIZED_VERT")? as c_long;
+        let horizontal = self.atom("_NET_WM_STATE_MAXIMIZED_HORZ")? as c_long;
+        self.send_client_message(
+            window,
+            "_NET_WM_STATE",
+            [NET_WM_STATE_REMOVE, vertical, horizontal, SOURCE_PAGER, 0],
+        )?;
+
+        // With north-west gravity the position refers to the frame's top-left corner,
+        // while the size is the client size without decorations.
+        let (left, right, top, bottom) = self.frame_extents(window)?;
+        self.send_client_message(
+            window,
+            "_NET_MOVERESIZE_WINDOW",
+            [
+                xlib::NorthWestGravity as c_long | MOVERESIZE_ALL_FIELDS | (SOURCE_PAGER << 12),
+                frame.x as c_long,
+                frame.y as c_long,
+                (frame.width - left - right).max(1) as c_long,
+                (frame.height - top - bottom).max(1) as c_long,
+            ],
+        )
+    }
+}
+
+impl Drop for X11WindowManager {
+    fn drop(&mut self) {
+        unsafe { (self.xlib.XCloseDisplay)(self.display) };
+        release_errors(self.display);
+    }
+}
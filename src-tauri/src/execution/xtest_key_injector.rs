@@ -0,0 +1,160 @@
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_uint};
+use std::ptr;
+
+use x11_dl::xlib::{self, Display, KeyCode, KeySym, Xlib};
+use x11_dl::xtest::Xf86vmode as XTest;
+
+use super::key_injector::{CharacterKey, KeyInjector};
+use super::x11_errors::{release_errors, sync, trap_errors};
+
+pub struct XTestKeyInjector {
+    xlib: Xlib,
+    xtest: XTest,
+    display: *mut Display,
+}
+
+impl XTestKeyInjector {
+    pub fn connect() -> Result<Self, String> {
+        let xlib = Xlib::open().map_err(|e| e.to_string())?;
+        let xtest = XTest::open().map_err(|e| e.to_string())?;
+
+        let display = unsafe { (xlib.XOpenDisplay)(ptr::null()) };
+        if display.is_null() {
+            return Err("Failed to open X display".to_string());
+        }
+        trap_errors(&xlib, display);
+
+        let (mut event_base, mut error_base, mut major, mut minor) = (0, 0, 0, 0);
+        let supported = unsafe {
+            (xtest.XTestQueryExtension)(
+                display,
+                &mut event_base,
+                &mut error_base,
+                &mut major,
+                &mut minor,
+            )
+        };
+        if supported == 0 {
+            unsafe { (xlib.XCloseDisplay)(display) };
+            release_errors(display);
+            return Err("XTest extension is not available".to_string());
+        }
+
+        Ok(Self {
+            xlib,
+            xtest,
+            display,
+        })
+    }
+
+    fn keycode(&self, key: &str) -> Result<KeyCode, String> {
+        let name = CString::new(key).map_err(|e| e.to_string())?;
+        let keysym = unsafe { (self.xlib.XStringToKeysym)(name.as_ptr()) };
+        if keysym == 0 {
+            return Err(format!("Unknown key: {}", key));
+        }
+
+        let keycode = unsafe { (self.xlib.XKeysymToKeycode)(self.display, keysym) };
+        if keycode == 0 {
+            return Err(format!(
+                "Key {} is not available in the current layout",
+                key
+            ));
+        }
+        Ok(keycode)
+    }
+
+    fn fake_key_event(&self, keycode: KeyCode, pressed: bool) -> Result<(), String> {
+        let is_press = if pressed { xlib::True } else { xlib::False };
+        let status = unsafe {
+            (self.xtest.XTestFakeKeyEvent)(self.display, c_uint::from(keycode), is_press, 0)
+        };
+        sync(&self.xlib, self.display)?;
+
+        if status == 0 {
+            return Err(format!(
+                "Failed to inject key event for keycode {}",
+                keycode
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl KeyInjector for XTestKeyInjector {
+    fn release_held_modifiers(&mut self) -> Result<(), String> {
+        let mut pressed_keys: [c_char; 32] = [0; 32];
+        unsafe { (self.xlib.XQueryKeymap)(self.display, pressed_keys.as_mut_ptr()) };
+
+        let modifier_map = unsafe { (self.xlib.XGetModifierMapping)(self.display) };
+        if modifier_map.is_null() {
+            return Err("Failed to read the modifier mapping".to_string());
+        }
+
+        let keycodes = unsafe {
+            let map = &*modifier_map;
+            // Eight modifier rows (Shift, Lock, Control, Mod1..Mod5) of max_keypermod keycodes.
+            std::slice::from_raw_parts(map.modifiermap, 8 * map.max_keypermod as usize).to_vec()
+        };
+        unsafe { (self.xlib.XFreeModifiermap)(modifier_map) };
+
+        for keycode in keycodes.into_iter().filter(|keycode| *keycode != 0) {
+            let byte = pressed_keys[usize::from(keycode / 8)] as u8;
+            if byte & (1 << (keycode % 8)) != 0 {
+                log::debug!("Releasing held modifier keycode {}", keycode);
+                self.fake_key_event(keycode, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn key_event(&mut self, key: &str, pressed: bool) -> Result<(), String> {
+        let keycode = self.keycode(key)?;
+        self.fake_key_event(keycode, pressed)
+    }
+
+    fn character_key(&mut self, character: char) -> Option<CharacterKey> {
+        // Latin-1 keysyms match their code points, everything else uses the
+        // Unicode keysym range.
+        let code_point = u32::from(character);
+        let keysym = if (0x20..=0x7e).contains(&code_point) || (0xa0..=0xff).contains(&code_point) {
+            KeySym::from(code_point)
+        } else {
+            KeySym::from(0x0100_0000 + code_point)
+        };
+
+        let keycode = unsafe { (self.xlib.XKeysymToKeycode)(self.display, keysym) };
+        if keycode == 0 {
+            return None;
+        }
+
+        // Levels 0 and 1 are reachable without and with Shift; higher levels (AltGr)
+        // are left to the clipboard fallback.
+        let shift = [false, true].into_iter().find(|shift| {
+            let level = i32::from(*shift);
+            unsafe { (self.xlib.XKeycodeToKeysym)(self.display, keycode, level) == keysym }
+        })?;
+
+        let name = unsafe { (self.xlib.XKeysymToString)(keysym) };
+        if name.is_null() {
+            return None;
+        }
+        let key = unsafe { CStr::from_ptr(name) }
+            .to_string_lossy()
+            .to_string();
+
+        Some(CharacterKey { key, shift })
+    }
+}
+
+impl Drop for XTestKeyInjector {
+    fn drop(&mut self) {
+        unsafe {
+            (self.xlib.XSync)(self.display, xlib::False);
+            (self.xlib.XCloseDisplay)(self.display);
+        }
+        release_errors(self.display);
+    }
+}
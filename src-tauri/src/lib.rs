@@ -5,8 +5,9 @@ pub mod execution;
 
 use analytics::setup_logging_plugin;
 use config::AppConfig;
-use definition::commands::{delete_shortcut, save_shortcut, get_shortcuts, get_shortcut_by_id, update_shortcut};
+use definition::commands::{delete_shortcut, save_shortcut, get_shortcuts, get_shortcut_by_id, get_shortcut_dependants, update_shortcut, check_action_paths, list_scripts, get_script, create_script, update_script, delete_script};
 use config::commands::get_raw_settings;
+use execution::commands::{list_installed_applications, list_running_actions, cancel_running_action, get_popup_request, answer_popup};
 use execution::setup_global_shortcut_plugin;
 use execution::ExecutionFacade;
 
@@ -26,8 +27,20 @@ pub fn run() {
             delete_shortcut, 
             get_shortcuts, 
             get_shortcut_by_id,
+            get_shortcut_dependants,
             update_shortcut,
-            get_raw_settings
+            check_action_paths,
+            list_scripts,
+            get_script,
+            create_script,
+            update_script,
+            delete_script,
+            get_raw_settings,
+            list_installed_applications,
+            list_running_actions,
+            cancel_running_action,
+            get_popup_request,
+            answer_popup
         ])
         .setup(|app| {
             log::info!("Setup started!");
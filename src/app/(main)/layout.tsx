@@ -0,0 +1,14 @@
+import Navigation from "@/shared/components/Navigation";
+
+export default function MainLayout({
+  children,
+}: {
+  children: React.ReactNode;
+}) {
+  return (
+    <div className="flex h-full">
+      <Navigation />
+      <main className="ml-24 flex-1">{children}</main>
+    </div>
+  );
+}
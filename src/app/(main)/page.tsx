@@ -8,6 +8,7 @@ import {
   deleteShortcut,
 } from "@/shared/store/slices/shortcutsSlice";
 import { ShortcutCard } from "@/shared/components/ShortcutCard";
+import { ShortcutsService } from "@/services/shortcuts/shortcuts.service";
 import ManageShortcuts from "@/features/ManageShortcut/ManageShortcut";
 import CreateNewShortcutModal from "@/features/CreateShortcutModal/CreateShortcutModal";
 import { Button } from "@/components/ui/button";
@@ -55,6 +56,18 @@ export default function Main() {
 
   const handleDelete = async (id: string) => {
     try {
+      const dependants = await ShortcutsService.getDependants(id);
+      if (
+        dependants.length > 0 &&
+        !window.confirm(
+          `This shortcut is run by ${dependants
+            .map((dependant) => dependant.command_name)
+            .join(", ")}, which will fail without it. Delete it anyway?`
+        )
+      ) {
+        return;
+      }
+
       await dispatch(deleteShortcut(id)).unwrap();
       if (selectedShortcut === id) {
         setSelectedShortcut(null);
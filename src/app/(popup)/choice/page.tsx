@@ -0,0 +1,59 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/button";
+import { ChoiceRequest } from "@/services/popup/popup.model";
+import { PopupService } from "@/services/popup/popup.service";
+
+export default function ChoicePage() {
+  const [request, setRequest] = useState<ChoiceRequest>();
+
+  useEffect(() => {
+    PopupService.getRequest<ChoiceRequest>()
+      .then(setRequest)
+      .catch((error) => console.error("Failed to load menu:", error));
+  }, []);
+
+  useEffect(() => {
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key === "Escape") {
+        PopupService.dismiss();
+        return;
+      }
+      const index = request?.choices.findIndex(
+        (choice) => choice.key?.toLowerCase() === event.key.toLowerCase()
+      );
+      if (index !== undefined && index >= 0) {
+        PopupService.answer(index);
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, [request]);
+
+  if (!request) {
+    return null;
+  }
+
+  return (
+    <div className="space-y-2">
+      <h1 className="text-sm font-semibold text-gray-500">{request.title}</h1>
+      {request.choices.map((choice, index) => (
+        <Button
+          key={index}
+          variant="outline"
+          className="w-full justify-between"
+          autoFocus={index === 0}
+          onClick={() => PopupService.answer(index)}
+        >
+          <span>{choice.name}</span>
+          {choice.key && (
+            <span className="px-2 py-0.5 bg-gray-100 dark:bg-gray-700 rounded text-xs">
+              {choice.key.toUpperCase()}
+            </span>
+          )}
+        </Button>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,74 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/button";
+import { ConfirmRequest } from "@/services/popup/popup.model";
+import { PopupService } from "@/services/popup/popup.service";
+
+export default function ConfirmPage() {
+  const [request, setRequest] = useState<ConfirmRequest>();
+  const [secondsLeft, setSecondsLeft] = useState(0);
+
+  useEffect(() => {
+    PopupService.getRequest<ConfirmRequest>()
+      .then((request) => {
+        setRequest(request);
+        setSecondsLeft(request.timeout_secs);
+      })
+      .catch((error) => console.error("Failed to load confirmation:", error));
+  }, []);
+
+  // The backend cancels on timeout, the countdown only shows when.
+  useEffect(() => {
+    if (secondsLeft <= 0) {
+      return;
+    }
+    const timer = setTimeout(() => setSecondsLeft((prev) => prev - 1), 1000);
+    return () => clearTimeout(timer);
+  }, [secondsLeft]);
+
+  useEffect(() => {
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key === "Escape") {
+        PopupService.answer(false);
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, []);
+
+  if (!request) {
+    return null;
+  }
+
+  return (
+    <div className="space-y-4">
+      <div>
+        <h1 className="text-lg font-semibold">Run {request.shortcut_name}?</h1>
+        <p className="text-sm text-gray-500">
+          {request.key_combination} · {request.actions.join(", ")}
+        </p>
+      </div>
+      <div className="flex justify-between items-center">
+        <span className="text-sm text-gray-500">
+          Cancels in {secondsLeft}s
+        </span>
+        <div className="flex space-x-2">
+          <Button
+            variant="outline"
+            autoFocus
+            onClick={() => PopupService.answer(false)}
+          >
+            Cancel
+          </Button>
+          <Button
+            variant="destructive"
+            onClick={() => PopupService.answer(true)}
+          >
+            Run
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,8 @@
+// Popup windows opened while a shortcut runs, without the app's navigation.
+export default function PopupLayout({
+  children,
+}: {
+  children: React.ReactNode;
+}) {
+  return <main className="h-full p-4">{children}</main>;
+}
@@ -0,0 +1,76 @@
+"use client";
+
+import { useEffect, useState } from "react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { PromptRequest } from "@/services/popup/popup.model";
+import { PopupService } from "@/services/popup/popup.service";
+
+export default function PromptPage() {
+  const [request, setRequest] = useState<PromptRequest>();
+  const [values, setValues] = useState<Record<string, string>>({});
+
+  useEffect(() => {
+    PopupService.getRequest<PromptRequest>()
+      .then((request) => {
+        setRequest(request);
+        setValues(
+          Object.fromEntries(
+            request.prompts.map((prompt) => [prompt.name, prompt.default ?? ""])
+          )
+        );
+      })
+      .catch((error) => console.error("Failed to load prompt:", error));
+  }, []);
+
+  useEffect(() => {
+    const handleKeyDown = (event: KeyboardEvent) => {
+      if (event.key === "Escape") {
+        PopupService.dismiss();
+      }
+    };
+    window.addEventListener("keydown", handleKeyDown);
+    return () => window.removeEventListener("keydown", handleKeyDown);
+  }, []);
+
+  if (!request) {
+    return null;
+  }
+
+  return (
+    <form
+      className="space-y-4"
+      onSubmit={(e) => {
+        e.preventDefault();
+        PopupService.answer(values);
+      }}
+    >
+      <h1 className="text-lg font-semibold">{request.shortcut_name}</h1>
+      {request.prompts.map((prompt, index) => (
+        <div key={prompt.name} className="space-y-2">
+          <Label htmlFor={prompt.name}>{prompt.label || prompt.name}</Label>
+          <Input
+            id={prompt.name}
+            type="text"
+            autoFocus={index === 0}
+            value={values[prompt.name] ?? ""}
+            onChange={(e) =>
+              setValues((prev) => ({ ...prev, [prompt.name]: e.target.value }))
+            }
+          />
+        </div>
+      ))}
+      <div className="flex justify-end space-x-2">
+        <Button
+          type="button"
+          variant="outline"
+          onClick={() => PopupService.dismiss()}
+        >
+          Cancel
+        </Button>
+        <Button type="submit">Run</Button>
+      </div>
+    </form>
+  );
+}
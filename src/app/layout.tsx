@@ -1,7 +1,6 @@
 import type { Metadata } from "next";
 import { Geist, Geist_Mono } from "next/font/google";
 import "./globals.css";
-import Navigation from "@/shared/components/Navigation";
 import { ReduxProvider } from "@/shared/providers/ReduxProvider";
 
 const geistSans = Geist({
@@ -29,12 +28,7 @@ export default function RootLayout({
       <body
         className={`${geistSans.variable} ${geistMono.variable} antialiased h-full`}
       >
-        <ReduxProvider>
-          <div className="flex h-full">
-            <Navigation />
-            <main className="ml-24 flex-1">{children}</main>
-          </div>
-        </ReduxProvider>
+        <ReduxProvider>{children}</ReduxProvider>
       </body>
     </html>
   );
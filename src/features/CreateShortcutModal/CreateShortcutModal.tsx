@@ -41,8 +41,22 @@ export default function CreateNewShortcutModal({
         {
           action_type: values.actionType,
           parameters: values.actionParams,
+          wait: values.wait,
+          on_failure: values.onFailure,
+          actions: values.groupActions,
+          condition: values.condition,
+          else_actions: values.elseActions,
+          alternate_actions: values.alternateActions,
+          choices: values.choices,
         },
+        ...(values.moreActions || []),
       ],
+      limits: values.limits,
+      execution_mode: values.executionMode,
+      cycle_reset_after_secs: values.cycleResetAfterSecs,
+      prompts: values.prompts,
+      require_confirmation: values.requireConfirmation,
+      confirmation_timeout_secs: values.confirmationTimeoutSecs,
     };
 
     try {
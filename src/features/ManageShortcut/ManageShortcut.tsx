@@ -1,4 +1,4 @@
-import React, { useEffect } from "react";
+import React, { useEffect, useState } from "react";
 import { useDispatch, useSelector } from "react-redux";
 import { AppDispatch, RootState } from "@/shared/store";
 import {
@@ -11,6 +11,7 @@ import {
   ShortcutFormValues,
 } from "@/shared/components/ShortcutForm/ShortcutForm";
 import { ActionType } from "@/services/shortcuts/shortcuts.model";
+import { ShortcutsService } from "@/services/shortcuts/shortcuts.service";
 
 interface ManageShortcutsProps {
   selectedShortcutId?: string | null;
@@ -23,6 +24,7 @@ export default function ManageShortcuts({
   const { detailLoading, createLoading, error, currentShortcut } = useSelector(
     (state: RootState) => state.shortcuts
   );
+  const [pathWarnings, setPathWarnings] = useState<string[]>([]);
 
   useEffect(() => {
     if (selectedShortcutId) {
@@ -39,11 +41,31 @@ export default function ManageShortcuts({
         {
           action_type: values.actionType,
           parameters: values.actionParams,
+          wait: values.wait,
+          on_failure: values.onFailure,
+          actions: values.groupActions,
+          condition: values.condition,
+          else_actions: values.elseActions,
+          alternate_actions: values.alternateActions,
+          choices: values.choices,
         },
+        ...(values.moreActions || []),
       ],
+      limits: values.limits,
+      execution_mode: values.executionMode,
+      cycle_reset_after_secs: values.cycleResetAfterSecs,
+      prompts: values.prompts,
+      require_confirmation: values.requireConfirmation,
+      confirmation_timeout_secs: values.confirmationTimeoutSecs,
     };
 
     try {
+      setPathWarnings(
+        values.checkPaths
+          ? await ShortcutsService.checkActionPaths(payload.actions)
+          : []
+      );
+
       if (selectedShortcutId) {
         await dispatch(
           updateShortcut({ id: selectedShortcutId, payload })
@@ -64,6 +86,20 @@ export default function ManageShortcuts({
         actionType:
           currentShortcut.actions[0]?.action_type || ActionType.OpenFolder,
         actionParams: currentShortcut.actions[0]?.parameters || {},
+        wait: currentShortcut.actions[0]?.wait,
+        onFailure: currentShortcut.actions[0]?.on_failure,
+        groupActions: currentShortcut.actions[0]?.actions,
+        condition: currentShortcut.actions[0]?.condition,
+        elseActions: currentShortcut.actions[0]?.else_actions,
+        alternateActions: currentShortcut.actions[0]?.alternate_actions,
+        choices: currentShortcut.actions[0]?.choices,
+        limits: currentShortcut.limits,
+        moreActions: currentShortcut.actions.slice(1),
+        executionMode: currentShortcut.execution_mode,
+        cycleResetAfterSecs: currentShortcut.cycle_reset_after_secs,
+        prompts: currentShortcut.prompts,
+        requireConfirmation: currentShortcut.require_confirmation,
+        confirmationTimeoutSecs: currentShortcut.confirmation_timeout_secs,
       }
     : undefined;
 
@@ -74,6 +110,13 @@ export default function ManageShortcuts({
   return (
     <div className="space-y-6">
       {error && <div className="text-red-500 p-4">Error: {error}</div>}
+      {pathWarnings.length > 0 && (
+        <div className="text-yellow-600 p-4">
+          {pathWarnings.map((warning) => (
+            <div key={warning}>Warning: {warning}</div>
+          ))}
+        </div>
+      )}
 
       <ShortcutForm
         initialValues={initialValues}
@@ -0,0 +1,22 @@
+import { ActionType, ParameterPrompt } from "../shortcuts/shortcuts.model";
+
+// Asks for the values of a shortcut's prompts, answered with the values by
+// prompt name
+export interface PromptRequest {
+  shortcut_name: string;
+  prompts: ParameterPrompt[];
+}
+
+// Asks which entry of a ChoiceMenu to run, answered with its index
+export interface ChoiceRequest {
+  title: string;
+  choices: { name: string; key?: string }[];
+}
+
+// Asks to confirm running a shortcut, answered with whether to run it
+export interface ConfirmRequest {
+  shortcut_name: string;
+  key_combination: string;
+  actions: ActionType[];
+  timeout_secs: number; // cancelled when not answered in time
+}
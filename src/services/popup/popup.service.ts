@@ -0,0 +1,17 @@
+import { apiClient } from "../api";
+
+// Popup windows are opened by the backend while a shortcut runs. Each asks
+// one request and closes once it is answered or dismissed.
+export const PopupService = {
+  getRequest: async <T>(): Promise<T> => {
+    return await apiClient.invoke<T>("get_popup_request");
+  },
+
+  answer: async (answer: unknown): Promise<void> => {
+    return await apiClient.invoke<void>("answer_popup", { answer });
+  },
+
+  dismiss: async (): Promise<void> => {
+    return await apiClient.invoke<void>("answer_popup", { answer: null });
+  },
+};
@@ -1,39 +1,202 @@
 export enum ActionType {
   OpenFolder = "OpenFolder",
   OpenFile = "OpenFile",
+  OpenUrl = "OpenUrl",
 
   OpenApplication = "OpenApplication",
   QuitApplication = "QuitApplication",
   HideApplication = "HideApplication",
   FocusApplication = "FocusApplication",
+  ToggleApplication = "ToggleApplication",
 
   MinimizeWindow = "MinimizeWindow",
   MaximizeWindow = "MaximizeWindow",
+  MoveResizeWindow = "MoveResizeWindow",
+
+  SendKeys = "SendKeys",
+  TypeText = "TypeText",
+  CopyToClipboard = "CopyToClipboard",
 
   RunShellScript = "RunShellScript",
+
+  CopyPath = "CopyPath",
+  MovePath = "MovePath",
+  RenamePath = "RenamePath",
+
+  Delay = "Delay",
+  Parallel = "Parallel",
+  RunShortcut = "RunShortcut",
+
+  SetState = "SetState",
+  Toggle = "Toggle",
+  ChoiceMenu = "ChoiceMenu",
+}
+
+export enum Weekday {
+  Monday = "Monday",
+  Tuesday = "Tuesday",
+  Wednesday = "Wednesday",
+  Thursday = "Thursday",
+  Friday = "Friday",
+  Saturday = "Saturday",
+  Sunday = "Sunday",
+}
+
+// Condition under which an action runs
+export type ActionCondition =
+  | { ExitCode: { equals: number } } // of the previous action
+  | { StdoutMatches: { pattern: string } } // regex
+  | { FileExists: { path: string } }
+  | { ProcessRunning: { name: string } }
+  | { TimeOfDay: { from: string; to: string } } // "HH:MM"
+  | { Weekday: { days: Weekday[] } }
+  | { FocusedApp: { app_name: string } };
+
+// When a Parallel group is done: after all of its actions, or the first success
+export enum JoinPolicy {
+  All = "All",
+  First = "First",
+}
+
+// Whose state an action reads and writes, the shortcut's own by default
+export enum StateScope {
+  Shortcut = "Shortcut",
+  Global = "Global",
+}
+
+export enum OverwritePolicy {
+  Skip = "Skip",
+  Replace = "Replace",
+  AutoSuffix = "AutoSuffix",
+}
+
+export enum GeometryUnit {
+  Pixels = "Pixels",
+  Percent = "Percent", // of the current monitor's work area
 }
 
+export enum SnapPosition {
+  LeftHalf = "LeftHalf",
+  RightHalf = "RightHalf",
+  TopHalf = "TopHalf",
+  BottomHalf = "BottomHalf",
+  LeftThird = "LeftThird",
+  CenterThird = "CenterThird",
+  RightThird = "RightThird",
+  LeftTwoThirds = "LeftTwoThirds",
+  RightTwoThirds = "RightTwoThirds",
+  Center = "Center",
+}
+
+export enum ScriptInterpreter {
+  Sh = "Sh",
+  Bash = "Bash",
+  Zsh = "Zsh",
+  Fish = "Fish",
+  Python3 = "Python3",
+  Node = "Node",
+  Custom = "Custom",
+}
+
+// String parameters may contain template variables: {clipboard},
+// {primary_selection}, {date:%Y-%m-%d}, {env:NAME}, {home}, {shortcut.name},
+// {window.title} and {window.app}, plus {prev.stdout} and {prev.exit_code} of
+// the previous action, stored state as {state.<key>} and {global.<key>}, and
+// the shortcut's prompts as {prompt.<name>}.
+// Use {{ and }} for literal braces; unknown names are kept as written. In
+// scripts a variable stands for a quoted value read from the environment, so
+// write it bare: echo {clipboard} or print({clipboard}).
 export interface BaseParameters {
   path?: string;
   source_path?: string;
   target_path?: string;
+  overwrite?: OverwritePolicy;
+  base_dir?: string; // relative paths resolve against it, home by default
+
+  url?: string; // e.g. https://jira/browse/{clipboard}
+  browser?: string;
+  browser_profile?: string;
 
   app_name?: string;
+  args?: string[]; // passed as is, without a shell
+  cwd?: string;
+  env?: Record<string, string>;
+  stdin_from_previous?: boolean; // the previous action's stdout
 
   key?: string;
   modifiers?: string[]; // ["cmd", "shift", etc.]
+  key_delay_ms?: number;
+  text?: string;
 
   window_width?: number;
   window_height?: number;
   window_x?: number;
   window_y?: number;
+  geometry_unit?: GeometryUnit;
+  snap_position?: SnapPosition;
 
   script?: string;
+  script_file?: string; // name of a managed script
+  interpreter?: ScriptInterpreter;
+  shebang?: string; // for ScriptInterpreter.Custom
+
+  delay_ms?: number;
+  join?: JoinPolicy; // for Parallel, All by default
+  shortcut_id?: string; // for RunShortcut
+
+  state_key?: string;
+  state_value?: string; // SetState without a value removes the key
+  state_scope?: StateScope;
 }
 
+// What the pipeline does when an action fails, Stop by default
+export type OnFailure =
+  | "Stop"
+  | "Continue"
+  | { Retry: { times: number; backoff_ms?: number } };
+
 export interface ShortcutAction {
   action_type: ActionType;
   parameters: BaseParameters;
+  wait?: boolean; // by default only scripts are waited for
+  on_failure?: OnFailure;
+  actions?: ShortcutAction[]; // a Parallel group's members, a Toggle's odd presses
+  condition?: ActionCondition;
+  else_actions?: ShortcutAction[]; // run when the condition doesn't hold
+  alternate_actions?: ShortcutAction[]; // a Toggle's even presses
+  choices?: MenuChoice[]; // for ChoiceMenu
+}
+
+// An entry of a ChoiceMenu, running its actions when picked
+export interface MenuChoice {
+  name: string;
+  key?: string; // single key accelerator
+  actions: ShortcutAction[];
+}
+
+// Safety limits for the RunShellScript actions of a shortcut
+export interface ScriptLimits {
+  timeout_secs?: number;
+  max_output_bytes?: number;
+  cpu_secs?: number;
+  memory_mb?: number;
+  nice?: number;
+  isolate_network?: boolean;
+  env_allowlist?: string[]; // PATH is always kept
+}
+
+// A value asked for in a popup when the shortcut fires, available to its
+// actions as {prompt.<name>}
+export interface ParameterPrompt {
+  name: string;
+  label?: string; // the name by default
+  default?: string;
+}
+
+// How a press runs the shortcut's actions
+export enum ExecutionMode {
+  Sequence = "Sequence", // all actions, one after another
+  Cycle = "Cycle", // the next action on each press, wrapping around
 }
 
 export interface Shortcut {
@@ -43,6 +206,12 @@ export interface Shortcut {
   description?: string;
   enabled: boolean;
   actions: ShortcutAction[];
+  limits?: ScriptLimits;
+  execution_mode?: ExecutionMode;
+  cycle_reset_after_secs?: number; // idle time before a cycle starts over
+  prompts?: ParameterPrompt[];
+  require_confirmation?: boolean;
+  confirmation_timeout_secs?: number; // unanswered confirmations cancel
 }
 
 export interface CreateShortcutPayload {
@@ -50,4 +219,74 @@ export interface CreateShortcutPayload {
   name: string;
   description?: string;
   actions: ShortcutAction[];
+  limits?: ScriptLimits;
+  execution_mode?: ExecutionMode;
+  cycle_reset_after_secs?: number;
+  prompts?: ParameterPrompt[];
+  require_confirmation?: boolean;
+  confirmation_timeout_secs?: number;
+}
+
+export interface ActionResult {
+  action_type: ActionType;
+  success: boolean;
+  message?: string;
+  skipped: boolean; // its condition didn't hold and there was no else branch
+  attempts: number;
+  duration_ms: number;
+  exit_code?: number;
+  stdout?: string;
+  steps: ActionResult[]; // results of a group or an else branch
+}
+
+export interface ShortcutEventPayload {
+  shortcut_id: string;
+  command_name: string;
+  state: "Pressed" | "Released";
+  results: ActionResult[];
+}
+
+export interface InstalledApplication {
+  id: string;
+  name: string;
+  icon?: string; // data URL
+}
+
+export interface ScriptSummary {
+  name: string;
+  shebang?: string;
+}
+
+// Payload of the "action-completed" event, one per supervised process.
+export enum ExceededLimit {
+  Timeout = "Timeout",
+  Output = "Output",
+  Cpu = "Cpu",
+}
+
+export interface ProcessRun {
+  shortcut_id: string;
+  command_name: string;
+  action_type: ActionType;
+  pid: number;
+  started_at: string;
+  duration_ms: number;
+  exit_code?: number;
+  signal?: number;
+  success: boolean;
+  cancelled: boolean;
+  limit_exceeded?: ExceededLimit;
+  stdout: string;
+  stderr: string;
+  stdout_truncated: boolean;
+  stderr_truncated: boolean;
+}
+
+export interface RunningAction {
+  shortcut_id: string;
+  command_name: string;
+  action_type: ActionType;
+  pid: number;
+  program: string;
+  started_at: string;
 }
@@ -1,5 +1,12 @@
 import { apiClient } from "../api";
-import { Shortcut, CreateShortcutPayload } from "./shortcuts.model";
+import {
+  Shortcut,
+  CreateShortcutPayload,
+  ShortcutAction,
+  InstalledApplication,
+  ScriptSummary,
+  RunningAction,
+} from "./shortcuts.model";
 
 export const ShortcutsService = {
   getAll: async (): Promise<Shortcut[]> => {
@@ -21,6 +28,56 @@ export const ShortcutsService = {
     return await apiClient.invoke<Shortcut>("update_shortcut", { id, payload });
   },
 
+  checkActionPaths: async (actions: ShortcutAction[]): Promise<string[]> => {
+    return await apiClient.invoke<string[]>("check_action_paths", { actions });
+  },
+
+  listInstalledApplications: async (): Promise<InstalledApplication[]> => {
+    return await apiClient.invoke<InstalledApplication[]>(
+      "list_installed_applications"
+    );
+  },
+
+  listScripts: async (): Promise<ScriptSummary[]> => {
+    return await apiClient.invoke<ScriptSummary[]>("list_scripts");
+  },
+
+  getScript: async (name: string): Promise<string> => {
+    return await apiClient.invoke<string>("get_script", { name });
+  },
+
+  createScript: async (name: string, content: string): Promise<void> => {
+    await apiClient.invoke("create_script", { name, content });
+  },
+
+  updateScript: async (name: string, content: string): Promise<void> => {
+    await apiClient.invoke("update_script", { name, content });
+  },
+
+  deleteScript: async (name: string): Promise<void> => {
+    await apiClient.invoke("delete_script", { name });
+  },
+
+  listRunningActions: async (): Promise<RunningAction[]> => {
+    return await apiClient.invoke<RunningAction[]>("list_running_actions");
+  },
+
+  cancelRunningAction: async (
+    shortcutId: string,
+    pid?: number
+  ): Promise<number> => {
+    return await apiClient.invoke<number>("cancel_running_action", {
+      shortcutId,
+      pid,
+    });
+  },
+
+  getDependants: async (id: string): Promise<Shortcut[]> => {
+    return await apiClient.invoke<Shortcut[]>("get_shortcut_dependants", {
+      id,
+    });
+  },
+
   delete: async (id: string): Promise<void> => {
     await apiClient.invoke("delete_shortcut", { id });
   },
@@ -0,0 +1,281 @@
+import React, { useId } from "react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  ActionType,
+  MenuChoice,
+  ShortcutAction,
+} from "@/services/shortcuts/shortcuts.model";
+import { ActionParametersFields } from "./ActionParametersFields";
+import { ConditionFields } from "./ConditionFields";
+import { OnFailureFields } from "./OnFailureFields";
+import { newAction, spawnsProcess } from "./ShortcutForm.model";
+
+// The three editors render each other for nested actions, so they share a file.
+
+interface ActionEditorProps {
+  action: ShortcutAction;
+  onChange: (action: ShortcutAction) => void;
+  shortcutName: string;
+  children?: React.ReactNode; // extra fields, after the parameters
+}
+
+export function ActionEditor({
+  action,
+  onChange,
+  shortcutName,
+  children,
+}: ActionEditorProps) {
+  const waitId = useId();
+  const actionType = action.action_type;
+
+  const update = (changes: Partial<ShortcutAction>) =>
+    onChange({ ...action, ...changes });
+
+  return (
+    <>
+      <div className="space-y-2">
+        <Label>Action Type</Label>
+        <Select
+          value={actionType}
+          onValueChange={(value: ActionType) => update({ action_type: value })}
+        >
+          <SelectTrigger>
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {Object.values(ActionType).map((type) => (
+              <SelectItem key={type} value={type}>
+                {type}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+
+      <ActionParametersFields
+        actionType={actionType}
+        parameters={action.parameters}
+        onChange={(parameters) => update({ parameters })}
+        shortcutName={shortcutName}
+      />
+
+      {actionType === ActionType.Parallel && (
+        <ActionListEditor
+          label="Actions Run Together"
+          actions={action.actions}
+          onChange={(actions) => update({ actions })}
+          shortcutName={shortcutName}
+        />
+      )}
+
+      {actionType === ActionType.Toggle && (
+        <>
+          <ActionListEditor
+            label="On Odd Presses Run"
+            actions={action.actions}
+            onChange={(actions) => update({ actions })}
+            shortcutName={shortcutName}
+          />
+          <ActionListEditor
+            label="On Even Presses Run"
+            actions={action.alternate_actions}
+            onChange={(alternate_actions) => update({ alternate_actions })}
+            shortcutName={shortcutName}
+          />
+        </>
+      )}
+
+      {actionType === ActionType.ChoiceMenu && (
+        <ChoiceListEditor
+          choices={action.choices}
+          onChange={(choices) => update({ choices })}
+          shortcutName={shortcutName}
+        />
+      )}
+
+      {children}
+
+      {spawnsProcess(actionType) && (
+        <div className="flex items-center space-x-2">
+          <input
+            id={waitId}
+            type="checkbox"
+            checked={action.wait ?? actionType === ActionType.RunShellScript}
+            onChange={(e) => update({ wait: e.target.checked })}
+          />
+          <Label htmlFor={waitId}>
+            Wait for the process to finish before the next action
+          </Label>
+        </div>
+      )}
+      <ConditionFields
+        condition={action.condition}
+        onChange={(condition) => update({ condition })}
+      />
+      {action.condition && (
+        <ActionListEditor
+          label="Otherwise Run"
+          emptyText="Nothing, the action is skipped"
+          actions={action.else_actions}
+          onChange={(else_actions) => update({ else_actions })}
+          shortcutName={shortcutName}
+        />
+      )}
+      <OnFailureFields
+        onFailure={action.on_failure}
+        onChange={(on_failure) => update({ on_failure })}
+      />
+    </>
+  );
+}
+
+interface ActionListEditorProps {
+  label: string;
+  emptyText?: string;
+  actions?: ShortcutAction[];
+  onChange: (actions?: ShortcutAction[]) => void; // undefined once empty
+  shortcutName: string;
+}
+
+export function ActionListEditor({
+  label,
+  emptyText = "No actions",
+  actions = [],
+  onChange,
+  shortcutName,
+}: ActionListEditorProps) {
+  const change = (next: ShortcutAction[]) =>
+    onChange(next.length > 0 ? next : undefined);
+
+  return (
+    <div className="space-y-2">
+      <div className="flex justify-between items-center">
+        <Label>{label}</Label>
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={() => change([...actions, newAction()])}
+        >
+          Add Action
+        </Button>
+      </div>
+      {actions.length === 0 && (
+        <div className="text-sm text-gray-500">{emptyText}</div>
+      )}
+      {actions.map((action, index) => (
+        <div key={index} className="space-y-4 rounded-md border p-3">
+          <div className="flex justify-between items-center">
+            <span className="text-sm font-medium">Action {index + 1}</span>
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() =>
+                change(actions.filter((_, otherIndex) => otherIndex !== index))
+              }
+            >
+              Remove
+            </Button>
+          </div>
+          <ActionEditor
+            action={action}
+            onChange={(changed) =>
+              change(
+                actions.map((other, otherIndex) =>
+                  otherIndex === index ? changed : other
+                )
+              )
+            }
+            shortcutName={shortcutName}
+          />
+        </div>
+      ))}
+    </div>
+  );
+}
+
+interface ChoiceListEditorProps {
+  choices?: MenuChoice[];
+  onChange: (choices?: MenuChoice[]) => void;
+  shortcutName: string;
+}
+
+function ChoiceListEditor({
+  choices = [],
+  onChange,
+  shortcutName,
+}: ChoiceListEditorProps) {
+  const change = (next: MenuChoice[]) =>
+    onChange(next.length > 0 ? next : undefined);
+
+  const handleChoiceChange = (index: number, choice: MenuChoice) =>
+    change(
+      choices.map((other, otherIndex) => (otherIndex === index ? choice : other))
+    );
+
+  return (
+    <div className="space-y-2">
+      <div className="flex justify-between items-center">
+        <Label>Choices</Label>
+        <Button
+          variant="ghost"
+          size="sm"
+          onClick={() => change([...choices, { name: "", actions: [] }])}
+        >
+          Add Choice
+        </Button>
+      </div>
+      {choices.map((choice, index) => (
+        <div key={index} className="space-y-4 rounded-md border p-3">
+          <div className="flex space-x-2">
+            <Input
+              type="text"
+              placeholder="Name, e.g. Left half"
+              value={choice.name}
+              onChange={(e) =>
+                handleChoiceChange(index, { ...choice, name: e.target.value })
+              }
+            />
+            <Input
+              type="text"
+              placeholder="Key, e.g. l"
+              maxLength={1}
+              value={choice.key ?? ""}
+              onChange={(e) =>
+                handleChoiceChange(index, {
+                  ...choice,
+                  key: e.target.value || undefined,
+                })
+              }
+            />
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() =>
+                change(choices.filter((_, otherIndex) => otherIndex !== index))
+              }
+            >
+              Remove
+            </Button>
+          </div>
+          <ActionListEditor
+            label="Runs"
+            actions={choice.actions}
+            onChange={(actions) =>
+              handleChoiceChange(index, { ...choice, actions: actions || [] })
+            }
+            shortcutName={shortcutName}
+          />
+        </div>
+      ))}
+    </div>
+  );
+}
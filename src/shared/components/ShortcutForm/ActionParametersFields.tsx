@@ -0,0 +1,538 @@
+import React, { useEffect, useId, useState } from "react";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  BaseParameters,
+  ActionType,
+  OverwritePolicy,
+  InstalledApplication,
+  ScriptInterpreter,
+  ScriptSummary,
+  JoinPolicy,
+  Shortcut,
+  StateScope,
+} from "@/services/shortcuts/shortcuts.model";
+import { ShortcutsService } from "@/services/shortcuts/shortcuts.service";
+import { launchesApplication, parseNumber } from "./ShortcutForm.model";
+
+const INLINE_SCRIPT = "__inline__";
+
+const parseEnv = (text: string): Record<string, string> | undefined => {
+  const entries = text
+    .split("\n")
+    .map((line) => line.split("="))
+    .filter(([name, ...value]) => name.trim() && value.length > 0)
+    .map(([name, ...value]) => [name.trim(), value.join("=")]);
+
+  return entries.length > 0 ? Object.fromEntries(entries) : undefined;
+};
+
+interface ActionParametersFieldsProps {
+  actionType: ActionType;
+  parameters: BaseParameters;
+  onChange: (parameters: BaseParameters) => void;
+  shortcutName: string; // left out of the shortcuts RunShortcut can pick
+}
+
+export function ActionParametersFields({
+  actionType,
+  parameters,
+  onChange,
+  shortcutName,
+}: ActionParametersFieldsProps) {
+  const stdinId = useId();
+
+  const [argsText, setArgsText] = useState(parameters.args?.join("\n") || "");
+  const [envText, setEnvText] = useState(
+    Object.entries(parameters.env || {})
+      .map(([name, value]) => `${name}=${value}`)
+      .join("\n")
+  );
+
+  const [installedApplications, setInstalledApplications] = useState<
+    InstalledApplication[]
+  >([]);
+
+  const [scripts, setScripts] = useState<ScriptSummary[]>([]);
+
+  const [shortcuts, setShortcuts] = useState<Shortcut[]>([]);
+
+  useEffect(() => {
+    if (actionType !== ActionType.RunShortcut) {
+      return;
+    }
+    ShortcutsService.getAll()
+      .then(setShortcuts)
+      .catch((error) => console.error("Failed to list shortcuts:", error));
+  }, [actionType]);
+
+  useEffect(() => {
+    if (actionType !== ActionType.RunShellScript) {
+      return;
+    }
+    ShortcutsService.listScripts()
+      .then(setScripts)
+      .catch((error) => console.error("Failed to list scripts:", error));
+  }, [actionType]);
+
+  useEffect(() => {
+    if (
+      !launchesApplication(actionType) ||
+      installedApplications.length > 0
+    ) {
+      return;
+    }
+    ShortcutsService.listInstalledApplications()
+      .then(setInstalledApplications)
+      .catch((error) =>
+        console.error("Failed to list installed applications:", error)
+      );
+  }, [actionType, installedApplications.length]);
+
+  const handleParamChange = <T extends keyof BaseParameters>(
+    param: T,
+    value: BaseParameters[T]
+  ) => {
+    onChange({
+      ...parameters,
+      [param]: value,
+    });
+  };
+
+  return (
+    <>
+      {(actionType === ActionType.OpenFolder ||
+        actionType === ActionType.OpenFile) && (
+        <div className="space-y-2">
+          <Label>Path</Label>
+          <Input
+            type="text"
+            placeholder="e.g. ~/Notes/{date:%Y-%m-%d}.md or ~/Downloads/*.pdf"
+            value={parameters.path ?? ""}
+            onChange={(e) => handleParamChange("path", e.target.value)}
+          />
+        </div>
+      )}
+
+      {actionType === ActionType.OpenUrl && (
+        <>
+          <div className="space-y-2">
+            <Label>URL</Label>
+            <Input
+              type="text"
+              placeholder="e.g. https://jira/browse/{clipboard}"
+              value={parameters.url ?? ""}
+              onChange={(e) => handleParamChange("url", e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>Browser (optional)</Label>
+            <Input
+              type="text"
+              placeholder="e.g. firefox or google-chrome"
+              value={parameters.browser ?? ""}
+              onChange={(e) => handleParamChange("browser", e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>Browser Profile (optional)</Label>
+            <Input
+              type="text"
+              placeholder="e.g. work"
+              value={parameters.browser_profile ?? ""}
+              onChange={(e) =>
+                handleParamChange("browser_profile", e.target.value)
+              }
+            />
+          </div>
+        </>
+      )}
+
+      {(actionType === ActionType.OpenApplication ||
+        actionType === ActionType.QuitApplication ||
+        actionType === ActionType.HideApplication ||
+        actionType === ActionType.FocusApplication ||
+        actionType === ActionType.ToggleApplication ||
+        actionType === ActionType.MinimizeWindow ||
+        actionType === ActionType.MaximizeWindow ||
+        actionType === ActionType.MoveResizeWindow) && (
+        <div className="space-y-2">
+          <Label>Application Name</Label>
+          <Input
+            type="text"
+            placeholder="Enter application name"
+            value={parameters.app_name ?? ""}
+            onChange={(e) => handleParamChange("app_name", e.target.value)}
+          />
+          {launchesApplication(actionType) &&
+            installedApplications.length > 0 && (
+              <Select
+                value=""
+                onValueChange={(value) => handleParamChange("app_name", value)}
+              >
+                <SelectTrigger>
+                  <SelectValue placeholder="Or pick an installed application" />
+                </SelectTrigger>
+                <SelectContent>
+                  {installedApplications.map((application) => (
+                    <SelectItem key={application.id} value={application.id}>
+                      <span className="flex items-center gap-2">
+                        {application.icon && (
+                          // eslint-disable-next-line @next/next/no-img-element
+                          <img
+                            src={application.icon}
+                            alt=""
+                            className="h-4 w-4"
+                          />
+                        )}
+                        {application.name}
+                      </span>
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            )}
+        </div>
+      )}
+
+      {actionType === ActionType.SendKeys && (
+        <div className="space-y-2">
+          <Label>Keys</Label>
+          <Input
+            type="text"
+            placeholder="e.g. ctrl+shift+c or Down Down Return"
+            value={parameters.key ?? ""}
+            onChange={(e) => handleParamChange("key", e.target.value)}
+          />
+        </div>
+      )}
+
+      {actionType === ActionType.Delay && (
+        <div className="space-y-2">
+          <Label>Delay (milliseconds)</Label>
+          <Input
+            type="number"
+            placeholder="e.g. 500"
+            value={parameters.delay_ms ?? ""}
+            onChange={(e) =>
+              handleParamChange("delay_ms", parseNumber(e.target.value))
+            }
+          />
+        </div>
+      )}
+
+      {actionType === ActionType.RunShortcut && (
+        <div className="space-y-2">
+          <Label>Shortcut to Run</Label>
+          <Select
+            value={parameters.shortcut_id}
+            onValueChange={(value) => handleParamChange("shortcut_id", value)}
+          >
+            <SelectTrigger>
+              <SelectValue placeholder="Select a shortcut" />
+            </SelectTrigger>
+            <SelectContent>
+              {shortcuts
+                .filter((other) => other.command_name !== shortcutName)
+                .map((other) => (
+                  <SelectItem key={other.id} value={other.id}>
+                    {other.command_name}
+                  </SelectItem>
+                ))}
+            </SelectContent>
+          </Select>
+        </div>
+      )}
+
+      {(actionType === ActionType.SetState ||
+        actionType === ActionType.Toggle) && (
+        <>
+          <div className="space-y-2">
+            <Label>State Key</Label>
+            <Input
+              type="text"
+              placeholder={
+                actionType === ActionType.Toggle ? "toggle" : "e.g. last_ticket"
+              }
+              value={parameters.state_key ?? ""}
+              onChange={(e) =>
+                handleParamChange("state_key", e.target.value || undefined)
+              }
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>Shared With</Label>
+            <Select
+              value={parameters.state_scope || StateScope.Shortcut}
+              onValueChange={(value: StateScope) =>
+                handleParamChange("state_scope", value)
+              }
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={StateScope.Shortcut}>
+                  Only this shortcut
+                </SelectItem>
+                <SelectItem value={StateScope.Global}>All shortcuts</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
+        </>
+      )}
+
+      {actionType === ActionType.SetState && (
+        <div className="space-y-2">
+          <Label>Value</Label>
+          <Input
+            type="text"
+            placeholder="e.g. {prev.stdout}, leave empty to remove the key"
+            value={parameters.state_value ?? ""}
+            onChange={(e) =>
+              handleParamChange("state_value", e.target.value || undefined)
+            }
+          />
+        </div>
+      )}
+
+      {actionType === ActionType.Parallel && (
+        <div className="space-y-2">
+          <Label>Done When</Label>
+          <Select
+            value={parameters.join || JoinPolicy.All}
+            onValueChange={(value: JoinPolicy) =>
+              handleParamChange("join", value)
+            }
+          >
+            <SelectTrigger>
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              <SelectItem value={JoinPolicy.All}>All actions finished</SelectItem>
+              <SelectItem value={JoinPolicy.First}>
+                The first action succeeded
+              </SelectItem>
+            </SelectContent>
+          </Select>
+        </div>
+      )}
+
+      {(actionType === ActionType.TypeText ||
+        actionType === ActionType.CopyToClipboard) && (
+        <div className="space-y-2">
+          <Label>Text</Label>
+          <textarea
+            className="flex min-h-[80px] w-full rounded-md border border-input bg-transparent px-3 py-2 text-sm"
+            placeholder={
+              actionType === ActionType.TypeText
+                ? "Enter text to type"
+                : "e.g. {prev.stdout}"
+            }
+            value={parameters.text ?? ""}
+            onChange={(e) => handleParamChange("text", e.target.value)}
+          />
+        </div>
+      )}
+
+      {actionType === ActionType.RunShellScript && (
+        <>
+          <div className="space-y-2">
+            <Label>Interpreter</Label>
+            <Select
+              value={parameters.interpreter || ScriptInterpreter.Sh}
+              onValueChange={(value: ScriptInterpreter) =>
+                handleParamChange("interpreter", value)
+              }
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {Object.values(ScriptInterpreter).map((interpreter) => (
+                  <SelectItem key={interpreter} value={interpreter}>
+                    {interpreter}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+          {parameters.interpreter === ScriptInterpreter.Custom && (
+            <div className="space-y-2">
+              <Label>Shebang</Label>
+              <Input
+                type="text"
+                placeholder="e.g. #!/usr/bin/env ruby"
+                value={parameters.shebang ?? ""}
+                onChange={(e) => handleParamChange("shebang", e.target.value)}
+              />
+            </div>
+          )}
+          <div className="space-y-2">
+            <Label>Script</Label>
+            <Select
+              value={parameters.script_file || INLINE_SCRIPT}
+              onValueChange={(value) =>
+                handleParamChange(
+                  "script_file",
+                  value === INLINE_SCRIPT ? undefined : value
+                )
+              }
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value={INLINE_SCRIPT}>Inline script</SelectItem>
+                {scripts.map((script) => (
+                  <SelectItem key={script.name} value={script.name}>
+                    {script.name}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+          {!parameters.script_file && (
+            <textarea
+              className="flex min-h-[120px] w-full rounded-md border border-input bg-transparent px-3 py-2 font-mono text-sm"
+              placeholder="Enter script"
+              value={parameters.script ?? ""}
+              onChange={(e) => handleParamChange("script", e.target.value)}
+            />
+          )}
+        </>
+      )}
+
+      {(launchesApplication(actionType) ||
+        actionType === ActionType.RunShellScript) && (
+        <>
+          <div className="space-y-2">
+            <Label>Arguments</Label>
+            <textarea
+              className="flex min-h-[60px] w-full rounded-md border border-input bg-transparent px-3 py-2 text-sm"
+              placeholder={
+                actionType === ActionType.RunShellScript
+                  ? "One argument per line, available as $1, $2, ..."
+                  : "One argument per line"
+              }
+              value={argsText}
+              onChange={(e) => {
+                setArgsText(e.target.value);
+                handleParamChange(
+                  "args",
+                  e.target.value ? e.target.value.split("\n") : undefined
+                );
+              }}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>Working Directory</Label>
+            <Input
+              type="text"
+              placeholder="Defaults to your home directory"
+              value={parameters.cwd ?? ""}
+              onChange={(e) => handleParamChange("cwd", e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>Environment Variables</Label>
+            <textarea
+              className="flex min-h-[60px] w-full rounded-md border border-input bg-transparent px-3 py-2 text-sm"
+              placeholder="One NAME=value per line"
+              value={envText}
+              onChange={(e) => {
+                setEnvText(e.target.value);
+                handleParamChange("env", parseEnv(e.target.value));
+              }}
+            />
+          </div>
+          <div className="flex items-center space-x-2">
+            <input
+              id={stdinId}
+              type="checkbox"
+              checked={parameters.stdin_from_previous ?? false}
+              onChange={(e) =>
+                handleParamChange("stdin_from_previous", e.target.checked)
+              }
+            />
+            <Label htmlFor={stdinId}>
+              Pipe the previous action&apos;s output to stdin
+            </Label>
+          </div>
+        </>
+      )}
+
+      {(actionType === ActionType.CopyPath ||
+        actionType === ActionType.MovePath ||
+        actionType === ActionType.RenamePath) && (
+        <>
+          <div className="space-y-2">
+            <Label>Source Path</Label>
+            <Input
+              type="text"
+              placeholder="e.g. ~/Downloads/report.pdf"
+              value={parameters.source_path ?? ""}
+              onChange={(e) => handleParamChange("source_path", e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>
+              {actionType === ActionType.RenamePath
+                ? "New Name or Path"
+                : "Target Path"}
+            </Label>
+            <Input
+              type="text"
+              placeholder="e.g. ~/Documents"
+              value={parameters.target_path ?? ""}
+              onChange={(e) => handleParamChange("target_path", e.target.value)}
+            />
+          </div>
+          <div className="space-y-2">
+            <Label>If Target Exists</Label>
+            <Select
+              value={parameters.overwrite || OverwritePolicy.Skip}
+              onValueChange={(value: OverwritePolicy) =>
+                handleParamChange("overwrite", value)
+              }
+            >
+              <SelectTrigger>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                {Object.values(OverwritePolicy).map((policy) => (
+                  <SelectItem key={policy} value={policy}>
+                    {policy}
+                  </SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+        </>
+      )}
+
+      {(actionType === ActionType.OpenFolder ||
+        actionType === ActionType.OpenFile ||
+        actionType === ActionType.CopyPath ||
+        actionType === ActionType.MovePath ||
+        actionType === ActionType.RenamePath) && (
+        <div className="space-y-2">
+          <Label>Base Directory for Relative Paths</Label>
+          <Input
+            type="text"
+            placeholder="Defaults to your home directory"
+            value={parameters.base_dir ?? ""}
+            onChange={(e) => handleParamChange("base_dir", e.target.value)}
+          />
+        </div>
+      )}
+    </>
+  );
+}
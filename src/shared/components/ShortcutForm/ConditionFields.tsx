@@ -0,0 +1,175 @@
+import React from "react";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { ActionCondition, Weekday } from "@/services/shortcuts/shortcuts.model";
+
+const ALWAYS = "Always";
+
+const conditionLabels = {
+  ExitCode: "Previous exit code is",
+  StdoutMatches: "Previous output matches",
+  FileExists: "File exists",
+  ProcessRunning: "Process is running",
+  TimeOfDay: "Time of day is between",
+  Weekday: "Weekday is one of",
+  FocusedApp: "Focused app is",
+};
+
+type ConditionKind = keyof typeof conditionLabels;
+
+const defaultCondition = (kind: ConditionKind): ActionCondition => {
+  switch (kind) {
+    case "ExitCode":
+      return { ExitCode: { equals: 0 } };
+    case "StdoutMatches":
+      return { StdoutMatches: { pattern: "" } };
+    case "FileExists":
+      return { FileExists: { path: "" } };
+    case "ProcessRunning":
+      return { ProcessRunning: { name: "" } };
+    case "TimeOfDay":
+      return { TimeOfDay: { from: "09:00", to: "17:00" } };
+    case "Weekday":
+      return { Weekday: { days: [] } };
+    case "FocusedApp":
+      return { FocusedApp: { app_name: "" } };
+  }
+};
+
+interface ConditionFieldsProps {
+  condition?: ActionCondition;
+  onChange: (condition?: ActionCondition) => void;
+}
+
+export function ConditionFields({ condition, onChange }: ConditionFieldsProps) {
+  const kind = condition
+    ? (Object.keys(condition)[0] as ConditionKind)
+    : undefined;
+
+  return (
+    <div className="space-y-2">
+      <Label>Run Only When</Label>
+      <Select
+        value={kind || ALWAYS}
+        onValueChange={(value) =>
+          onChange(
+            value === ALWAYS
+              ? undefined
+              : defaultCondition(value as ConditionKind)
+          )
+        }
+      >
+        <SelectTrigger>
+          <SelectValue />
+        </SelectTrigger>
+        <SelectContent>
+          <SelectItem value={ALWAYS}>Always</SelectItem>
+          {Object.entries(conditionLabels).map(([value, label]) => (
+            <SelectItem key={value} value={value}>
+              {label}
+            </SelectItem>
+          ))}
+        </SelectContent>
+      </Select>
+
+      {condition && "ExitCode" in condition && (
+        <Input
+          type="number"
+          value={condition.ExitCode.equals}
+          onChange={(e) =>
+            onChange({ ExitCode: { equals: Number(e.target.value) } })
+          }
+        />
+      )}
+      {condition && "StdoutMatches" in condition && (
+        <Input
+          type="text"
+          placeholder="Regular expression"
+          value={condition.StdoutMatches.pattern}
+          onChange={(e) =>
+            onChange({ StdoutMatches: { pattern: e.target.value } })
+          }
+        />
+      )}
+      {condition && "FileExists" in condition && (
+        <Input
+          type="text"
+          placeholder="e.g. ~/.config/app/lock"
+          value={condition.FileExists.path}
+          onChange={(e) => onChange({ FileExists: { path: e.target.value } })}
+        />
+      )}
+      {condition && "ProcessRunning" in condition && (
+        <Input
+          type="text"
+          placeholder="e.g. firefox"
+          value={condition.ProcessRunning.name}
+          onChange={(e) =>
+            onChange({ ProcessRunning: { name: e.target.value } })
+          }
+        />
+      )}
+      {condition && "TimeOfDay" in condition && (
+        <div className="flex space-x-2">
+          <Input
+            type="time"
+            value={condition.TimeOfDay.from}
+            onChange={(e) =>
+              onChange({
+                TimeOfDay: { ...condition.TimeOfDay, from: e.target.value },
+              })
+            }
+          />
+          <Input
+            type="time"
+            value={condition.TimeOfDay.to}
+            onChange={(e) =>
+              onChange({
+                TimeOfDay: { ...condition.TimeOfDay, to: e.target.value },
+              })
+            }
+          />
+        </div>
+      )}
+      {condition && "Weekday" in condition && (
+        <div className="flex flex-wrap gap-2">
+          {Object.values(Weekday).map((day) => (
+            <label key={day} className="flex items-center space-x-1 text-sm">
+              <input
+                type="checkbox"
+                checked={condition.Weekday.days.includes(day)}
+                onChange={(e) =>
+                  onChange({
+                    Weekday: {
+                      days: e.target.checked
+                        ? [...condition.Weekday.days, day]
+                        : condition.Weekday.days.filter((d) => d !== day),
+                    },
+                  })
+                }
+              />
+              <span>{day.slice(0, 3)}</span>
+            </label>
+          ))}
+        </div>
+      )}
+      {condition && "FocusedApp" in condition && (
+        <Input
+          type="text"
+          placeholder="e.g. firefox"
+          value={condition.FocusedApp.app_name}
+          onChange={(e) =>
+            onChange({ FocusedApp: { app_name: e.target.value } })
+          }
+        />
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,74 @@
+import React from "react";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { OnFailure } from "@/services/shortcuts/shortcuts.model";
+import { parseNumber } from "./ShortcutForm.model";
+
+interface OnFailureFieldsProps {
+  onFailure?: OnFailure;
+  onChange: (onFailure?: OnFailure) => void;
+}
+
+export function OnFailureFields({ onFailure, onChange }: OnFailureFieldsProps) {
+  return (
+    <div className="space-y-2">
+      <Label>On Failure</Label>
+      <div className="flex space-x-2">
+        <Select
+          value={typeof onFailure === "object" ? "Retry" : onFailure || "Stop"}
+          onValueChange={(value) =>
+            onChange(
+              value === "Retry" ? { Retry: { times: 3 } } : (value as OnFailure)
+            )
+          }
+        >
+          <SelectTrigger>
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value="Stop">Stop</SelectItem>
+            <SelectItem value="Continue">Continue</SelectItem>
+            <SelectItem value="Retry">Retry</SelectItem>
+          </SelectContent>
+        </Select>
+        {typeof onFailure === "object" && (
+          <>
+            <Input
+              type="number"
+              placeholder="Times"
+              value={onFailure.Retry.times}
+              onChange={(e) =>
+                onChange({
+                  Retry: {
+                    ...onFailure.Retry,
+                    times: parseNumber(e.target.value) ?? 0,
+                  },
+                })
+              }
+            />
+            <Input
+              type="number"
+              placeholder="Backoff (ms)"
+              value={onFailure.Retry.backoff_ms ?? ""}
+              onChange={(e) =>
+                onChange({
+                  Retry: {
+                    ...onFailure.Retry,
+                    backoff_ms: parseNumber(e.target.value),
+                  },
+                })
+              }
+            />
+          </>
+        )}
+      </div>
+    </div>
+  );
+}
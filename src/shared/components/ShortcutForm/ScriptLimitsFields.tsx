@@ -0,0 +1,79 @@
+import React, { useState } from "react";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { ScriptLimits } from "@/services/shortcuts/shortcuts.model";
+import { parseNumber } from "./ShortcutForm.model";
+
+const limitFields: [
+  "timeout_secs" | "max_output_bytes" | "cpu_secs" | "memory_mb" | "nice",
+  string,
+][] = [
+  ["timeout_secs", "Timeout (seconds)"],
+  ["max_output_bytes", "Max Output (bytes)"],
+  ["cpu_secs", "CPU Time (seconds)"],
+  ["memory_mb", "Memory (MB)"],
+  ["nice", "Niceness (-20 to 19)"],
+];
+
+interface ScriptLimitsFieldsProps {
+  limits: ScriptLimits;
+  onChange: (limits: ScriptLimits) => void;
+}
+
+export function ScriptLimitsFields({ limits, onChange }: ScriptLimitsFieldsProps) {
+  const [allowlistText, setAllowlistText] = useState(
+    limits.env_allowlist?.join("\n") || ""
+  );
+
+  return (
+    <div className="space-y-2">
+      <Label>Safety Limits</Label>
+      <div className="grid grid-cols-2 gap-2">
+        {limitFields.map(([field, label]) => (
+          <Input
+            key={field}
+            type="number"
+            placeholder={label}
+            value={limits[field] ?? ""}
+            onChange={(e) =>
+              onChange({
+                ...limits,
+                [field]: parseNumber(e.target.value),
+              })
+            }
+          />
+        ))}
+      </div>
+      <div className="flex items-center space-x-2">
+        <input
+          id="isolate-network"
+          type="checkbox"
+          checked={limits.isolate_network ?? false}
+          onChange={(e) =>
+            onChange({
+              ...limits,
+              isolate_network: e.target.checked,
+            })
+          }
+        />
+        <Label htmlFor="isolate-network">Block network access</Label>
+      </div>
+      <textarea
+        className="flex min-h-[60px] w-full rounded-md border border-input bg-transparent px-3 py-2 text-sm"
+        placeholder="Inherit only these environment variables, one per line (PATH is always kept)"
+        value={allowlistText}
+        onChange={(e) => {
+          setAllowlistText(e.target.value);
+          const names = e.target.value
+            .split("\n")
+            .map((name) => name.trim())
+            .filter(Boolean);
+          onChange({
+            ...limits,
+            env_allowlist: names.length > 0 ? names : undefined,
+          });
+        }}
+      />
+    </div>
+  );
+}
@@ -1,16 +1,143 @@
 import {
   BaseParameters,
   ActionType,
+  ShortcutAction,
 } from "@/services/shortcuts/shortcuts.model";
 
 export const actionParameterRequirements = {
   [ActionType.OpenFolder]: { required: ["path"] },
   [ActionType.OpenFile]: { required: ["path"] },
+  [ActionType.OpenUrl]: { required: ["url"] },
   [ActionType.OpenApplication]: { required: ["app_name"] },
   [ActionType.QuitApplication]: { required: ["app_name"] },
   [ActionType.HideApplication]: { required: ["app_name"] },
   [ActionType.FocusApplication]: { required: ["app_name"] },
-  [ActionType.MinimizeWindow]: { required: ["window_width", "window_height"] },
-  [ActionType.MaximizeWindow]: { required: ["window_width", "window_height"] },
-  [ActionType.RunShellScript]: { required: ["script"] },
+  [ActionType.ToggleApplication]: { required: ["app_name"] },
+  [ActionType.MinimizeWindow]: { required: [] },
+  [ActionType.MaximizeWindow]: { required: [] },
+  [ActionType.MoveResizeWindow]: { required: [] },
+  [ActionType.SendKeys]: { required: ["key"] },
+  [ActionType.TypeText]: { required: ["text"] },
+  [ActionType.CopyToClipboard]: { required: ["text"] },
+  [ActionType.RunShellScript]: { required: [] }, // script or script_file
+  [ActionType.CopyPath]: { required: ["source_path", "target_path"] },
+  [ActionType.MovePath]: { required: ["source_path", "target_path"] },
+  [ActionType.RenamePath]: { required: ["source_path", "target_path"] },
+  [ActionType.Delay]: { required: ["delay_ms"] },
+  [ActionType.Parallel]: { required: [] }, // members are in actions
+  [ActionType.RunShortcut]: { required: ["shortcut_id"] },
+  [ActionType.SetState]: { required: ["state_key"] },
+  [ActionType.Toggle]: { required: [] }, // toggled actions are in actions
+  [ActionType.ChoiceMenu]: { required: [] }, // entries are in choices
 } satisfies Record<ActionType, { required: (keyof BaseParameters)[] }>;
+
+export const launchesApplication = (type: ActionType) =>
+  type === ActionType.OpenApplication || type === ActionType.ToggleApplication;
+
+export const spawnsProcess = (type: ActionType) =>
+  launchesApplication(type) ||
+  type === ActionType.OpenFolder ||
+  type === ActionType.OpenFile ||
+  type === ActionType.OpenUrl ||
+  type === ActionType.RunShellScript;
+
+export const parseNumber = (text: string): number | undefined =>
+  text.trim() === "" ? undefined : Number(text);
+
+export const newAction = (): ShortcutAction => ({
+  action_type: ActionType.OpenFolder,
+  parameters: {},
+});
+
+// Drops what the action's type doesn't use, like the members of a group whose
+// type was changed, so they aren't saved along with it.
+export const normalizeAction = (action: ShortcutAction): ShortcutAction => {
+  const type = action.action_type;
+  const list = (actions?: ShortcutAction[]) =>
+    actions?.length ? actions.map(normalizeAction) : undefined;
+
+  return {
+    action_type: type,
+    parameters: action.parameters,
+    wait: spawnsProcess(type) ? action.wait : undefined,
+    on_failure: action.on_failure,
+    actions:
+      type === ActionType.Parallel || type === ActionType.Toggle
+        ? list(action.actions)
+        : undefined,
+    condition: action.condition,
+    else_actions: action.condition ? list(action.else_actions) : undefined,
+    alternate_actions:
+      type === ActionType.Toggle ? list(action.alternate_actions) : undefined,
+    choices:
+      type === ActionType.ChoiceMenu
+        ? action.choices?.map((choice) => ({
+            ...choice,
+            actions: choice.actions.map(normalizeAction),
+          }))
+        : undefined,
+  };
+};
+
+// The first problem keeping the action, or one nested in it, from being
+// saved, prefixed with where the nested one is.
+export const validateAction = (action: ShortcutAction): string | undefined => {
+  const type = action.action_type;
+  const { required } = actionParameterRequirements[type];
+  const parameters = action.parameters;
+
+  if (
+    !required.every(
+      (param) => parameters[param] !== undefined && parameters[param] !== ""
+    )
+  ) {
+    return `Please fill in required parameters: ${required.join(", ")}`;
+  }
+  if (
+    type === ActionType.RunShellScript &&
+    !parameters.script &&
+    !parameters.script_file
+  ) {
+    return "Please enter a script or pick a script file";
+  }
+  if (
+    (type === ActionType.Parallel || type === ActionType.Toggle) &&
+    !action.actions?.length
+  ) {
+    return type === ActionType.Parallel
+      ? "Please add the group's actions"
+      : "Please add the actions run on odd presses";
+  }
+  if (type === ActionType.ChoiceMenu) {
+    if (!action.choices?.length) {
+      return "Please add the menu's choices";
+    }
+    if (!action.choices.every((choice) => choice.name.trim())) {
+      return "Please name every choice of the menu";
+    }
+  }
+
+  const nested: [string, ShortcutAction[] | undefined][] = [
+    [type === ActionType.Toggle ? "Odd presses" : "Group", action.actions],
+    ["Even presses", action.alternate_actions],
+    ["Otherwise", action.else_actions],
+    ...(action.choices || []).map(
+      (choice): [string, ShortcutAction[]] => [choice.name, choice.actions]
+    ),
+  ];
+  return validateActions(nested);
+};
+
+export const validateActions = (
+  lists: [string, ShortcutAction[] | undefined][]
+): string | undefined => {
+  for (const [label, actions] of lists) {
+    for (const [index, action] of (actions || []).entries()) {
+      const problem = validateAction(action);
+      if (problem) {
+        return `${label}, action ${index + 1}: ${problem}`;
+      }
+    }
+  }
+  return undefined;
+};
@@ -18,14 +18,50 @@ import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
 import {
   BaseParameters,
   ActionType,
+  ScriptLimits,
+  OnFailure,
+  ShortcutAction,
+  ActionCondition,
+  ExecutionMode,
+  ParameterPrompt,
+  MenuChoice,
 } from "@/services/shortcuts/shortcuts.model";
-import { actionParameterRequirements } from "./ShortcutForm.model";
+import {
+  normalizeAction,
+  parseNumber,
+  validateAction,
+  validateActions,
+} from "./ShortcutForm.model";
+import { ActionEditor, ActionListEditor } from "./ActionEditor";
+import { ScriptLimitsFields } from "./ScriptLimitsFields";
+
+const emptyParameters = (): BaseParameters => ({
+  path: "",
+  app_name: "",
+  script: "",
+});
+
 export interface ShortcutFormValues {
   shortcut: string;
   name: string;
   description: string;
   actionType: ActionType;
   actionParams: BaseParameters;
+  checkPaths: boolean;
+  limits?: ScriptLimits;
+  wait?: boolean;
+  onFailure?: OnFailure;
+  groupActions?: ShortcutAction[];
+  condition?: ActionCondition;
+  elseActions?: ShortcutAction[];
+  alternateActions?: ShortcutAction[];
+  choices?: MenuChoice[];
+  moreActions?: ShortcutAction[]; // the shortcut's actions after this one
+  executionMode?: ExecutionMode;
+  cycleResetAfterSecs?: number;
+  prompts?: ParameterPrompt[];
+  requireConfirmation?: boolean;
+  confirmationTimeoutSecs?: number;
 }
 
 interface ShortcutFormProps {
@@ -52,19 +88,55 @@ export function ShortcutForm({
     initialValues?.description || ""
   );
   const [isFocused, setIsFocused] = useState(false);
-  const [actionType, setActionType] = useState<ActionType>(
-    initialValues?.actionType || ActionType.OpenFolder
+
+  const [checkPaths, setCheckPaths] = useState(
+    initialValues?.checkPaths ?? true
   );
 
-  const [actionParams, setActionParams] = useState<BaseParameters>({
-    path: "",
-    app_name: "",
-    script: "",
-    ...initialValues?.actionParams,
+  const [action, setAction] = useState<ShortcutAction>({
+    action_type: initialValues?.actionType || ActionType.OpenFolder,
+    parameters: {
+      ...emptyParameters(),
+      ...initialValues?.actionParams,
+    },
+    wait: initialValues?.wait,
+    on_failure: initialValues?.onFailure,
+    actions: initialValues?.groupActions,
+    condition: initialValues?.condition,
+    else_actions: initialValues?.elseActions,
+    alternate_actions: initialValues?.alternateActions,
+    choices: initialValues?.choices,
   });
+  // Remounts the editors, whose text fields keep their own state, on clearing.
+  const [resetCount, setResetCount] = useState(0);
+
+  const [executionMode, setExecutionMode] = useState(
+    initialValues?.executionMode || ExecutionMode.Sequence
+  );
+  const [moreActions, setMoreActions] = useState(initialValues?.moreActions);
+  const [cycleResetAfterSecs, setCycleResetAfterSecs] = useState(
+    initialValues?.cycleResetAfterSecs
+  );
+
+  const [prompts, setPrompts] = useState<ParameterPrompt[]>(
+    initialValues?.prompts || []
+  );
+
+  const [requireConfirmation, setRequireConfirmation] = useState(
+    initialValues?.requireConfirmation ?? false
+  );
+  const [confirmationTimeoutSecs, setConfirmationTimeoutSecs] = useState(
+    initialValues?.confirmationTimeoutSecs
+  );
+
+  const [limits, setLimits] = useState<ScriptLimits>(
+    initialValues?.limits || {}
+  );
 
   const divRef = useRef<HTMLDivElement>(null);
 
+  const actionType = action.action_type;
+
   useEffect(() => {
     if (initialValues?.shortcut) {
       setShortcut(initialValues.shortcut.split("+"));
@@ -76,16 +148,6 @@ export function ShortcutForm({
     setSavedShortcut(shortcut.join("+"));
   }, [shortcut]);
 
-  const handleParamChange = <T extends keyof BaseParameters>(
-    param: T,
-    value: BaseParameters[T]
-  ) => {
-    setActionParams((prev) => ({
-      ...prev,
-      [param]: value,
-    }));
-  };
-
   const validateShortcut = (): boolean => {
     if (shortcut.length === 0 || name.trim() === "") {
       alert("Please set a shortcut and enter a name");
@@ -94,23 +156,40 @@ export function ShortcutForm({
     return true;
   };
 
-  const validateActionParams = (type: ActionType): boolean => {
-    const requiredParams = actionParameterRequirements[type].required;
-    const isValid = requiredParams.every(
-      (param) => actionParams[param] !== undefined && actionParams[param] !== ""
+  const handlePromptChange = (index: number, prompt: ParameterPrompt) => {
+    setPrompts((prev) =>
+      prev.map((other, otherIndex) => (otherIndex === index ? prompt : other))
+    );
+  };
+
+  const validatePrompts = (): boolean => {
+    const names = prompts.map((prompt) => prompt.name);
+    const isValid = names.every(
+      (name, index) => /^\w+$/.test(name) && names.indexOf(name) === index
     );
 
     if (!isValid) {
-      const required = actionParameterRequirements[type].required.join(", ");
-      alert(`Please fill in required parameters: ${required}`);
+      alert("Prompt names must be unique and use only letters, digits and _");
       return false;
     }
-
     return true;
   };
 
-  const handleActionTypeChange = (value: ActionType) => {
-    setActionType(value);
+  const validateActionTree = (): boolean => {
+    // Only what gets saved is checked, not the leftovers of a changed type.
+    const problem =
+      validateAction(normalizeAction(action)) ||
+      (executionMode === ExecutionMode.Cycle
+        ? validateActions([
+            ["Then cycle through", moreActions?.map(normalizeAction)],
+          ])
+        : undefined);
+
+    if (problem) {
+      alert(problem);
+      return false;
+    }
+    return true;
   };
 
   const handleKeyDown: KeyboardEventHandler<HTMLDivElement> = (event) => {
@@ -143,30 +222,60 @@ export function ShortcutForm({
     setSavedShortcut("");
   };
 
+  const clearActionParams = () => {
+    setAction((prev) => ({ ...prev, parameters: emptyParameters() }));
+    setResetCount((count) => count + 1);
+  };
+
   const clearForm = () => {
     setShortcut([]);
     setSavedShortcut("");
     setName("");
     setDescription("");
-    setActionType(ActionType.OpenFolder);
-    setActionParams({
-      path: "",
-      app_name: "",
-      script: "",
+    setAction({
+      action_type: ActionType.OpenFolder,
+      parameters: emptyParameters(),
     });
+    setLimits({});
+    setExecutionMode(ExecutionMode.Sequence);
+    setMoreActions(undefined);
+    setCycleResetAfterSecs(undefined);
+    setPrompts([]);
+    setRequireConfirmation(false);
+    setConfirmationTimeoutSecs(undefined);
+    setResetCount((count) => count + 1);
   };
 
   const handleSubmit = async () => {
-    if (!validateShortcut() || !validateActionParams(actionType)) {
+    if (!validateShortcut() || !validateActionTree() || !validatePrompts()) {
       return;
     }
 
+    const normalized = normalizeAction(action);
     const values = {
       shortcut: savedShortcut,
       name,
       description,
       actionType,
-      actionParams,
+      actionParams: normalized.parameters,
+      checkPaths,
+      limits: actionType === ActionType.RunShellScript ? limits : undefined,
+      wait: normalized.wait,
+      onFailure: normalized.on_failure,
+      groupActions: normalized.actions,
+      alternateActions: normalized.alternate_actions,
+      choices: normalized.choices,
+      condition: normalized.condition,
+      elseActions: normalized.else_actions,
+      moreActions: moreActions?.map(normalizeAction),
+      executionMode,
+      cycleResetAfterSecs:
+        executionMode === ExecutionMode.Cycle ? cycleResetAfterSecs : undefined,
+      prompts: prompts.length > 0 ? prompts : undefined,
+      requireConfirmation: requireConfirmation || undefined,
+      confirmationTimeoutSecs: requireConfirmation
+        ? confirmationTimeoutSecs
+        : undefined,
     };
 
     await onSubmit(values);
@@ -238,73 +347,165 @@ export function ShortcutForm({
             <Button
               variant="ghost"
               size="sm"
-              onClick={() =>
-                setActionParams({
-                  path: "",
-                  app_name: "",
-                  script: "",
-                })
-              }
+              onClick={clearActionParams}
             >
               Clear Parameters
             </Button>
           </div>
         </CardHeader>
         <CardContent className="space-y-4">
+          <ActionEditor
+            key={resetCount}
+            action={action}
+            onChange={setAction}
+            shortcutName={name}
+          >
+            {actionType === ActionType.RunShellScript && (
+              <ScriptLimitsFields limits={limits} onChange={setLimits} />
+            )}
+            {(actionType === ActionType.OpenFolder ||
+              actionType === ActionType.OpenFile ||
+              actionType === ActionType.CopyPath ||
+              actionType === ActionType.MovePath ||
+              actionType === ActionType.RenamePath) && (
+              <div className="flex items-center space-x-2">
+                <input
+                  id="check-paths"
+                  type="checkbox"
+                  checked={checkPaths}
+                  onChange={(e) => setCheckPaths(e.target.checked)}
+                />
+                <Label htmlFor="check-paths">
+                  Warn on save if the path does not exist
+                </Label>
+              </div>
+            )}
+          </ActionEditor>
+        </CardContent>
+      </Card>
+
+      <div className="space-y-2">
+        <Label>Each Press Runs</Label>
+        <Select
+          value={executionMode}
+          onValueChange={(value: ExecutionMode) => setExecutionMode(value)}
+        >
+          <SelectTrigger>
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            <SelectItem value={ExecutionMode.Sequence}>All actions</SelectItem>
+            <SelectItem value={ExecutionMode.Cycle}>
+              The next action in turn
+            </SelectItem>
+          </SelectContent>
+        </Select>
+      </div>
+      {executionMode === ExecutionMode.Cycle && (
+        <>
+          <ActionListEditor
+            key={resetCount}
+            label="Then Cycle Through"
+            actions={moreActions}
+            onChange={setMoreActions}
+            shortcutName={name}
+          />
           <div className="space-y-2">
-            <Label>Action Type</Label>
-            <Select value={actionType} onValueChange={handleActionTypeChange}>
-              <SelectTrigger>
-                <SelectValue />
-              </SelectTrigger>
-              <SelectContent>
-                {Object.values(ActionType).map((type) => (
-                  <SelectItem key={type} value={type}>
-                    {type}
-                  </SelectItem>
-                ))}
-              </SelectContent>
-            </Select>
+            <Label>Start Over After (seconds idle)</Label>
+            <Input
+              type="number"
+              placeholder="Never"
+              value={cycleResetAfterSecs ?? ""}
+              onChange={(e) =>
+                setCycleResetAfterSecs(parseNumber(e.target.value))
+              }
+            />
           </div>
+        </>
+      )}
 
-          {(actionType === ActionType.OpenFolder ||
-            actionType === ActionType.OpenFile) && (
-            <div className="space-y-2">
-              <Label>Path</Label>
-              <Input
-                type="text"
-                placeholder="Enter path"
-                value={actionParams?.path}
-                onChange={(e) => handleParamChange("path", e.target.value)}
-              />
-            </div>
-          )}
 
-          {actionType === ActionType.OpenApplication && (
-            <div className="space-y-2">
-              <Label>Application Name</Label>
-              <Input
-                type="text"
-                placeholder="Enter application name"
-                value={actionParams?.app_name}
-                onChange={(e) => handleParamChange("app_name", e.target.value)}
-              />
-            </div>
-          )}
+      <div className="space-y-2">
+        <div className="flex justify-between items-center">
+          <Label>Ask Before Running</Label>
+          <Button
+            variant="ghost"
+            size="sm"
+            onClick={() => setPrompts((prev) => [...prev, { name: "" }])}
+          >
+            Add Prompt
+          </Button>
+        </div>
+        {prompts.map((prompt, index) => (
+          <div key={index} className="flex space-x-2">
+            <Input
+              type="text"
+              placeholder="Name, used as {prompt.name}"
+              value={prompt.name}
+              onChange={(e) =>
+                handlePromptChange(index, { ...prompt, name: e.target.value })
+              }
+            />
+            <Input
+              type="text"
+              placeholder="Label"
+              value={prompt.label ?? ""}
+              onChange={(e) =>
+                handlePromptChange(index, {
+                  ...prompt,
+                  label: e.target.value || undefined,
+                })
+              }
+            />
+            <Input
+              type="text"
+              placeholder="Default"
+              value={prompt.default ?? ""}
+              onChange={(e) =>
+                handlePromptChange(index, {
+                  ...prompt,
+                  default: e.target.value || undefined,
+                })
+              }
+            />
+            <Button
+              variant="ghost"
+              size="sm"
+              onClick={() =>
+                setPrompts((prev) =>
+                  prev.filter((_, otherIndex) => otherIndex !== index)
+                )
+              }
+            >
+              Remove
+            </Button>
+          </div>
+        ))}
+      </div>
 
-          {actionType === ActionType.RunShellScript && (
-            <div className="space-y-2">
-              <Label>Shell Script</Label>
-              <Input
-                type="text"
-                placeholder="Enter shell script"
-                value={actionParams?.script}
-                onChange={(e) => handleParamChange("script", e.target.value)}
-              />
-            </div>
-          )}
-        </CardContent>
-      </Card>
+      <div className="space-y-2">
+        <div className="flex items-center space-x-2">
+          <input
+            id="require-confirmation"
+            type="checkbox"
+            checked={requireConfirmation}
+            onChange={(e) => setRequireConfirmation(e.target.checked)}
+          />
+          <Label htmlFor="require-confirmation">
+            Ask for confirmation before running
+          </Label>
+        </div>
+        {requireConfirmation && (
+          <Input
+            type="number"
+            placeholder="Cancel after (seconds), 30 by default"
+            value={confirmationTimeoutSecs ?? ""}
+            onChange={(e) =>
+              setConfirmationTimeoutSecs(parseNumber(e.target.value))
+            }
+          />
+        )}
+      </div>
 
       <div className="flex justify-end space-x-2 pt-4">
         {onCancel && (